@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use async_std::fs;
+use chrono::Utc;
+use rand_xoshiro::rand_core::{RngCore, SeedableRng};
+use rand_xoshiro::Xoshiro256StarStar;
+
+use crate::{dump::create_spoiler, hash, json, mkpsxiso, rand::patch, util, workspace::Workspace};
+
+struct BatchResult {
+    seed: u64,
+    result: anyhow::Result<(PathBuf, String)>,
+}
+
+async fn randomize(
+    workspace: &Workspace,
+    preset: &json::Preset,
+    pristine: &PathBuf,
+    output_root: &PathBuf,
+) -> anyhow::Result<(PathBuf, String)> {
+    // previous seeds write their patched files into the workspace
+    fs::remove_dir_all(workspace.extract_dir()).await?;
+    fs::remove_dir_all(workspace.build_dir()).await?;
+    fs::create_dir_all(workspace.build_dir()).await?;
+
+    util::copy_dir(pristine, &PathBuf::from(workspace.extract_dir())).await?;
+
+    let objects = patch(workspace, preset).await?;
+
+    let seed_hash = hash::seed_hash(preset, &objects)?;
+
+    create_spoiler(&objects, workspace, &seed_hash).await?;
+
+    mkpsxiso::build(workspace).await?;
+
+    let destination = workspace
+        .publish(output_root, &format!("{}", preset.randomizer.seed))
+        .await?;
+
+    Ok((destination, seed_hash.to_string()))
+}
+
+pub async fn batch(
+    path: &PathBuf,
+    preset_path: &Option<PathBuf>,
+    seeds: &[u64],
+    count: u32,
+    output_root: &PathBuf,
+) -> anyhow::Result<()> {
+    let preset = json::load_preset(preset_path)?;
+
+    let errors = preset.validate();
+    if !errors.is_empty() {
+        return Err(anyhow!(json::report(&errors)));
+    }
+
+    let mut rng = Xoshiro256StarStar::seed_from_u64(Utc::now().timestamp() as u64);
+
+    let mut seeds = seeds.to_vec();
+    seeds.extend((0..count).map(|_| rng.next_u64()));
+
+    if seeds.is_empty() {
+        return Err(anyhow!("no seeds, use --seeds and/or --count"));
+    }
+
+    let workspace = Workspace::new(path).await?;
+    let pristine = workspace.root.join("pristine");
+
+    mkpsxiso::extract(&workspace).await?;
+
+    util::copy_dir(&PathBuf::from(workspace.extract_dir()), &pristine).await?;
+
+    let mut results = Vec::new();
+
+    for seed in seeds {
+        let mut preset = preset.clone();
+        preset.randomizer.seed = seed;
+
+        let result = randomize(&workspace, &preset, &pristine, output_root).await;
+
+        match &result {
+            Ok((destination, _)) => println!("randomized into {}", destination.display()),
+            Err(err) => println!("seed {seed} failed: {err}"),
+        }
+
+        results.push(BatchResult { seed, result });
+    }
+
+    println!();
+    println!("{:<20} | {:<40} | {}", "seed", "output", "seed hash");
+
+    for entry in &results {
+        let (output, seed_hash) = match &entry.result {
+            Ok((destination, seed_hash)) => (destination.display().to_string(), seed_hash.clone()),
+            Err(err) => (String::from("-"), format!("failed: {err}")),
+        };
+
+        println!("{:<20} | {:<40} | {}", entry.seed, output, seed_hash);
+    }
+
+    let failed = results.iter().filter(|x| x.result.is_err()).count();
+    if failed > 0 {
+        return Err(anyhow!("{} of {} seeds failed", failed, results.len()));
+    }
+
+    Ok(())
+}
@@ -19,6 +19,25 @@ pub enum Command {
         #[command(subcommand)]
         action: ModAction,
     },
+    /// Generate one randomized ROM per seed from a single preset
+    Batch {
+        /// bin path
+        path: std::path::PathBuf,
+        /// randomizer preset json
+        #[clap(long)]
+        preset: Option<std::path::PathBuf>,
+        /// comma separated seeds
+        #[clap(long, value_delimiter = ',')]
+        seeds: Vec<u64>,
+        /// amount of random seeds to generate on top of --seeds
+        #[clap(long)]
+        #[arg(default_value_t = 0)]
+        count: u32,
+        /// directory the randomized ROMs are written to
+        #[clap(long)]
+        #[arg(default_value = "randomized")]
+        output_root: std::path::PathBuf,
+    },
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -52,6 +71,20 @@ pub struct Arguments {
     #[clap(short, long)]
     #[arg(default_value_t = false)]
     pub dump: bool,
+    /// directory the randomized ROMs are written to
+    #[clap(long)]
+    #[arg(default_value = "randomized")]
+    #[serde(default = "default_output_root")]
+    pub output_root: std::path::PathBuf,
+    /// rewrite the preset in the current schema version and exit
+    #[clap(long)]
+    #[arg(default_value_t = false)]
+    #[serde(default)]
+    pub normalize_preset: bool,
+}
+
+fn default_output_root() -> std::path::PathBuf {
+    std::path::PathBuf::from("randomized")
 }
 
 impl Default for Arguments {
@@ -62,6 +95,8 @@ impl Default for Arguments {
             seed: Some(Utc::now().timestamp() as u64),
             output: None,
             dump: false,
+            output_root: default_output_root(),
+            normalize_preset: false,
         }
     }
 }
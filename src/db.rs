@@ -38,11 +38,20 @@ pub fn init() -> anyhow::Result<()> {
                 id INTEGER PRIMARY KEY,
                 created_at INTEGER NOT NULL,
                 preset TEXT NOT NULL,
-                arguments TEXT NOT NULL
+                arguments TEXT NOT NULL,
+                seed_hash TEXT
             );",
         (),
     )?;
 
+    // history tables created before seed hashes existed
+    if conn
+        .prepare("SELECT seed_hash FROM history LIMIT 1")
+        .is_err()
+    {
+        conn.execute("ALTER TABLE history ADD COLUMN seed_hash TEXT", ())?;
+    }
+
     Ok(())
 }
 
@@ -52,13 +61,14 @@ pub struct History {
     pub created_at: i64,
     pub preset: String,
     pub arguments: String,
+    pub seed_hash: Option<String>,
 }
 
 pub fn last() -> rusqlite::Result<History> {
     let conn = get_conn()?;
 
     let mut result = conn.prepare(
-        "SELECT id, created_at, preset, arguments FROM history ORDER BY created_at DESC LIMIT 1",
+        "SELECT id, created_at, preset, arguments, seed_hash FROM history ORDER BY created_at DESC LIMIT 1",
     )?;
 
     let s = result.query_row([], |row| {
@@ -67,13 +77,14 @@ pub fn last() -> rusqlite::Result<History> {
             created_at: row.get(1)?,
             preset: row.get(2)?,
             arguments: row.get(3)?,
+            seed_hash: row.get(4)?,
         })
     })?;
 
     Ok(s)
 }
 
-pub fn insert(preset: &Preset, arguments: &Arguments) -> Result<(), Box<dyn Error>> {
+pub fn insert(preset: &Preset, arguments: &Arguments) -> Result<i64, Box<dyn Error>> {
     let conn = get_conn()?;
 
     conn.execute(
@@ -85,6 +96,17 @@ pub fn insert(preset: &Preset, arguments: &Arguments) -> Result<(), Box<dyn Erro
         ),
     )?;
 
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn set_seed_hash(id: i64, seed_hash: &str) -> rusqlite::Result<()> {
+    let conn = get_conn()?;
+
+    conn.execute(
+        "UPDATE history SET seed_hash = ?1 WHERE id = ?2",
+        (seed_hash, id),
+    )?;
+
     Ok(())
 }
 
@@ -92,7 +114,7 @@ pub fn history() -> rusqlite::Result<Vec<History>> {
     let conn = get_conn()?;
 
     let mut qres = conn.prepare(
-        "SELECT id, created_at, preset, arguments FROM history ORDER BY created_at DESC",
+        "SELECT id, created_at, preset, arguments, seed_hash FROM history ORDER BY created_at DESC",
     )?;
 
     let rows = qres.query_map([], |row| {
@@ -101,6 +123,7 @@ pub fn history() -> rusqlite::Result<Vec<History>> {
             created_at: row.get(1)?,
             preset: row.get(2)?,
             arguments: row.get(3)?,
+            seed_hash: row.get(4)?,
         })
     })?;
 
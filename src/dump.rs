@@ -1,6 +1,5 @@
 use std::{fs, io::Write};
 
-use anyhow::Context;
 use async_std::fs::{create_dir_all, File};
 use async_std::prelude::*;
 use binrw::BinWrite;
@@ -8,7 +7,10 @@ use std::io::Cursor;
 use dmw3_structs::{Pointer, StageEncounter, StageEncounterArea};
 use tar::{Builder, Header};
 
+use crate::hash::SeedHash;
 use crate::objects::{read_objects, Objects};
+use crate::spoiler::write_spoilers;
+use crate::workspace::Workspace;
 
 static DEFAULT_AREA: StageEncounterArea = StageEncounterArea {
     steps_inddex: 0,
@@ -67,14 +69,10 @@ static DEFAULT_ENCOUNTERS: [StageEncounter; 8] = [
     },
 ];
 
-pub async fn dump(path: &std::path::PathBuf) -> anyhow::Result<()> {
-    let objects = read_objects(path).await?;
+pub async fn dump(workspace: &Workspace) -> anyhow::Result<()> {
+    let objects = read_objects(workspace).await?;
 
-    let rom_name = path
-        .file_name()
-        .context("Failed to get file name")?
-        .to_str()
-        .context("Failed to convert to str")?;
+    let rom_name = &workspace.rom_name;
 
     fs::create_dir_all(format!("dump/{rom_name}"))?;
 
@@ -366,8 +364,8 @@ fn append_file<W: Write>(
 
 pub async fn create_spoiler(
     objects: &Objects,
-    path: &std::path::PathBuf,
-    file_name: &str,
+    workspace: &Workspace,
+    seed_hash: &SeedHash,
 ) -> anyhow::Result<()> {
     let mut enemy_stats_bytes = Vec::new();
     let mut encounter_bytes = Vec::new();
@@ -618,18 +616,13 @@ pub async fn create_spoiler(
 
     drop(tar_builder);
 
-    let rom_name = path
-        .file_name()
-        .context("Failed file name get")?
-        .to_str()
-        .context("Failed to_str conversion")?;
-
-    create_dir_all(format!("randomized/{}/{}", rom_name, file_name)).await?;
+    create_dir_all(workspace.build_dir()).await?;
 
-    let mut spoiler =
-        File::create(format!("randomized/{}/{}/spoiler.tar", rom_name, file_name)).await?;
+    let mut spoiler = File::create(format!("{}/spoiler.tar", workspace.build_dir())).await?;
 
     spoiler.write_all(&buffer).await?;
 
+    write_spoilers(objects, seed_hash, workspace.build_dir()).await?;
+
     Ok(())
 }
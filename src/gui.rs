@@ -5,7 +5,7 @@ use rand_xoshiro::rand_core::RngCore;
 use rand_xoshiro::rand_core::SeedableRng;
 
 use crate::gui::preset::history::get_mapped;
-use crate::json::Preset;
+use crate::json::{self, Preset};
 
 use crate::cli::Arguments;
 use crate::db;
@@ -14,11 +14,15 @@ use std::path::PathBuf;
 
 mod auctions;
 mod card_game;
+mod charisma;
 mod checkbox;
+mod drops;
 mod encounters;
 mod file_upload;
+mod item_pools;
 mod maps;
 mod models;
+mod moves;
 mod number_field;
 mod number_field_float;
 mod parties;
@@ -61,7 +65,8 @@ fn app() -> Element {
     });
     use_context_provider(|| {
         Signal::new(match db::last() {
-            Ok(history) => serde_json::from_str::<Preset>(history.preset.as_str())
+            Ok(history) => json::migrate(history.preset.as_str())
+                .map(|migrated| migrated.preset)
                 .unwrap_or(serde_json::from_str("{}").unwrap()),
             Err(_) => serde_json::from_str("{}").unwrap(),
         })
@@ -178,10 +183,13 @@ fn app() -> Element {
             div {
                 class: "column",
                 parties::parties {},
+                charisma::charisma {},
                 scaling::scaling {},
                 models::models {},
+                moves::moves {},
                 card_game::card_game {}
                 auctions::auctions {}
+                drops::drops {}
             },
             div {
                 class: "column",
@@ -189,6 +197,7 @@ fn app() -> Element {
                 encounters::encounters {},
                 maps::maps {},
                 party_exp_bits::party_exp_bits {},
+                item_pools::item_pools {},
             },
         }
     }
@@ -1,19 +1,21 @@
 use dioxus::prelude::*;
 
 use crate::{
-    gui::{checkbox, number_field},
-    json::{Preset, ShopItems},
+    gui::{checkbox, item_pools::invalid_class, number_field},
+    json::{invalid, Preset, ShopItems},
 };
 
 #[component]
 pub fn auctions() -> Element {
     let mut state = use_context::<Signal<Preset>>();
     let read_state = state();
+    let errors = read_state.validate();
 
     let enabled = read_state.randomizer.auctions.enabled;
 
     let auction_items = read_state.randomizer.auctions.auction_items;
     let auction_selected = read_state.randomizer.auctions.auction_items_pool.clone();
+    let item_pools = read_state.randomizer.item_pools.clone();
 
     let auction_values = read_state.randomizer.auctions.auction_values;
     let auction_values_min = read_state.randomizer.auctions.auction_values_min as i64;
@@ -60,9 +62,10 @@ pub fn auctions() -> Element {
                     },
                     select {
                         id: "auctions.auction_items_pool",
+                        class: invalid_class(&errors, "randomizer.auctions.auctionItemsPool"),
                         disabled: !enabled || !auction_items,
                         onchange: move |x: Event<FormData>| {
-                            state.write().randomizer.auctions.auction_items_pool = ShopItems::from(x.data.value().parse::<u8>().unwrap_or(0));
+                            state.write().randomizer.auctions.auction_items_pool = ShopItems::from_value(&x.data.value());
                         },
                         option {
                             value: "0",
@@ -79,6 +82,13 @@ pub fn auctions() -> Element {
                             selected: auction_selected == ShopItems::Ironmon,
                             "Ironmon"
                         },
+                        for pool in item_pools.iter() {
+                            option {
+                                value: "custom:{pool.name}",
+                                selected: auction_selected == ShopItems::Custom(pool.name.clone()),
+                                "{pool.name}"
+                            },
+                        }
                     }
                 },
             }
@@ -99,6 +109,7 @@ pub fn auctions() -> Element {
                 class: "left",
                 number_field::number_field {
                     id: "auctions.auction_values_min",
+                    invalid: invalid(&errors, "randomizer.auctions.auctionValuesMin"),
                     label: "Min",
                     disabled: !enabled || !auction_values,
                     onchange: move |x: i64| {
@@ -110,6 +121,7 @@ pub fn auctions() -> Element {
                 },
                 number_field::number_field {
                     id: "auctions.auction_values_max",
+                    invalid: invalid(&errors, "randomizer.auctions.auctionValuesMax"),
                     label: "Max",
                     disabled: !enabled || !auction_values,
                     onchange: move |x: i64| {
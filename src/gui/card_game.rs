@@ -1,7 +1,7 @@
 use dioxus::prelude::*;
 
 use crate::gui::number_field;
-use crate::json::Preset;
+use crate::json::{invalid, Preset};
 
 use crate::gui::checkbox;
 
@@ -10,6 +10,7 @@ pub fn card_game() -> Element {
     let mut preset_state = use_context::<Signal<Preset>>();
 
     let read_preset_state = preset_state();
+    let errors = read_preset_state.validate();
 
     let enabled = read_preset_state.randomizer.card_game.enabled;
     let buy_price = read_preset_state.randomizer.card_game.buy_price;
@@ -57,6 +58,7 @@ pub fn card_game() -> Element {
                 class: "left",
                 number_field::number_field {
                     id: "card_game.min_card_buy_price",
+                    invalid: invalid(&errors, "randomizer.cardGame.minCardBuyPrice"),
                     label: "Min",
                     disabled: !enabled || !buy_price,
                     onchange: move |x: i64| {
@@ -68,6 +70,7 @@ pub fn card_game() -> Element {
                 },
                 number_field::number_field {
                     id: "card_game.max_card_buy_price",
+                    invalid: invalid(&errors, "randomizer.cardGame.maxCardBuyPrice"),
                     label: "Max",
                     disabled: !enabled || !buy_price,
                     onchange: move |x: i64| {
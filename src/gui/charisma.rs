@@ -0,0 +1,86 @@
+use dioxus::prelude::*;
+
+use crate::{
+    gui::{checkbox, number_field},
+    json::{invalid, CharismaReqs, Preset},
+};
+
+#[component]
+pub fn charisma() -> Element {
+    let mut state = use_context::<Signal<Preset>>();
+    let read_state = state();
+    let errors = read_state.validate();
+
+    let enabled = read_state.randomizer.charisma.enabled;
+
+    let requirements = read_state.randomizer.charisma.requirements;
+    let multiplier = read_state.randomizer.charisma.multiplier as i64;
+
+    rsx! {
+        div {
+            class: "segment",
+            checkbox::checkbox {
+                label: "Charisma",
+                id: "charisma.enabled",
+                checked: enabled,
+                tooltip: "Rescale or randomize tamer charisma requirements",
+                onchange: move |x: bool| {
+                    state.write().randomizer.charisma.enabled = x;
+                }
+            }
+            div {
+                class: "left",
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        style: "width: 250px;",
+                        "Keep => vanilla requirements",
+                        br {},
+                        "Scale => vanilla requirements times the multiplier",
+                        br {},
+                        "Random => random increasing requirements up to the scaled vanilla maximum",
+                    },
+                    label {
+                        r#for: "charisma.requirements",
+                        "Tamer Requirements"
+                    },
+                    select {
+                        id: "charisma.requirements",
+                        disabled: !enabled,
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.charisma.requirements = CharismaReqs::from(x.data.value().parse::<u8>().unwrap_or(0));
+                        },
+                        option {
+                            value: "0",
+                            selected: requirements == CharismaReqs::Keep,
+                            "Keep"
+                        },
+                        option {
+                            value: "1",
+                            selected: requirements == CharismaReqs::Scale,
+                            "Scale"
+                        },
+                        option {
+                            value: "2",
+                            selected: requirements == CharismaReqs::Random,
+                            "Random"
+                        },
+                    }
+                },
+                number_field::number_field {
+                    id: "charisma.multiplier",
+                    invalid: invalid(&errors, "randomizer.charisma.multiplier"),
+                    label: "Multiplier %",
+                    disabled: !enabled || requirements == CharismaReqs::Keep,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.charisma.multiplier = x as u16;
+                    },
+                    value: multiplier,
+                    min: 1,
+                    max: 1000
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use dioxus::prelude::*;
+
+use crate::{
+    gui::{checkbox, item_pools::invalid_class, number_field},
+    json::{invalid, Preset, ShopItems},
+};
+
+#[component]
+pub fn drops() -> Element {
+    let mut state = use_context::<Signal<Preset>>();
+    let read_state = state();
+    let errors = read_state.validate();
+
+    let enabled = read_state.randomizer.drops.enabled;
+    let selected = read_state.randomizer.drops.items_pool.clone();
+    let item_pools = read_state.randomizer.item_pools.clone();
+
+    let min_drop_rate = read_state.randomizer.drops.min_drop_rate as i64;
+    let max_drop_rate = read_state.randomizer.drops.max_drop_rate as i64;
+
+    let keep_boss_drops = read_state.randomizer.drops.keep_boss_drops;
+    let no_drops = read_state.randomizer.drops.no_drops;
+
+    rsx! {
+        div {
+            class: "segment",
+            checkbox::checkbox {
+                label: "Drops",
+                id: "drops.enabled",
+                checked: enabled,
+                tooltip: "Randomize enemy drops",
+                onchange: move |x: bool| {
+                    state.write().randomizer.drops.enabled = x;
+                }
+            }
+            div {
+                class: "left",
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        style: "width: 200px;",
+                        "Buyable => all buyable items",
+                        br {},
+                        "Sellable => all sellable items",
+                        br {},
+                        "Ironmon => special pool",
+                    },
+                    label {
+                        r#for: "drops.items_pool",
+                        "Drop Pool"
+                    },
+                    select {
+                        id: "drops.items_pool",
+                        class: invalid_class(&errors, "randomizer.drops.itemsPool"),
+                        disabled: !enabled || no_drops,
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.drops.items_pool = ShopItems::from_value(&x.data.value());
+                        },
+                        option {
+                            value: "0",
+                            selected: selected == ShopItems::Buyable,
+                            "Buyable"
+                        },
+                        option {
+                            value: "1",
+                            selected: selected == ShopItems::Sellable,
+                            "Sellable"
+                        },
+                        option {
+                            value: "2",
+                            selected: selected == ShopItems::Ironmon,
+                            "Ironmon"
+                        },
+                        for pool in item_pools.iter() {
+                            option {
+                                value: "custom:{pool.name}",
+                                selected: selected == ShopItems::Custom(pool.name.clone()),
+                                "{pool.name}"
+                            },
+                        }
+                    }
+                },
+            }
+            div {
+                class: "left",
+                number_field::number_field {
+                    id: "drops.min_drop_rate",
+                    invalid: invalid(&errors, "randomizer.drops.minDropRate"),
+                    label: "Min rate",
+                    disabled: !enabled || no_drops,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.drops.min_drop_rate = x as u16;
+                    },
+                    value: min_drop_rate,
+                    min: 0,
+                    max: max_drop_rate
+                },
+                number_field::number_field {
+                    id: "drops.max_drop_rate",
+                    invalid: invalid(&errors, "randomizer.drops.maxDropRate"),
+                    label: "Max rate",
+                    disabled: !enabled || no_drops,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.drops.max_drop_rate = x as u16;
+                    },
+                    value: max_drop_rate,
+                    min: min_drop_rate,
+                    max: 100
+                },
+            }
+            div {
+                class: "left",
+                checkbox::checkbox {
+                    id: "drops.keep_boss_drops",
+                    label: "Keep boss drops",
+                    disabled: !enabled,
+                    checked: keep_boss_drops,
+                    tooltip: "Bosses keep their reward drops",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.drops.keep_boss_drops = x;
+                    },
+                },
+                checkbox::checkbox {
+                    id: "drops.no_drops",
+                    label: "No drops",
+                    disabled: !enabled,
+                    checked: no_drops,
+                    tooltip: "Enemies drop nothing (TNT Ball stays)",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.drops.no_drops = x;
+                    },
+                },
+            }
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use dioxus::prelude::*;
 
-use crate::json::TNTStrategy;
+use crate::json::{invalid, BossStrategy, EncounterTier, TNTStrategy, TeamTheme, TechPool};
+use crate::rand::{MAX_RESISTANCE, MAX_TEAM_SIZE};
 
 use crate::json::Preset;
 
-use crate::gui::checkbox;
+use crate::gui::{checkbox, number_field};
 
 pub fn encounters() -> Element {
     let mut state = use_context::<Signal<Preset>>();
@@ -18,6 +19,25 @@ pub fn encounters() -> Element {
     let keep_zanbamon = read_state.randomizer.encounters.keep_zanbamon;
     let keep_galacticmon = read_state.randomizer.encounters.keep_galacticmon;
 
+    let tier = read_state.randomizer.encounters.tier;
+    let level_band = read_state.randomizer.encounters.level_band;
+
+    let teams = read_state.randomizer.encounters.teams;
+    let min_team_size = read_state.randomizer.encounters.min_team_size;
+    let max_team_size = read_state.randomizer.encounters.max_team_size;
+    let team_theme = read_state.randomizer.encounters.team_theme;
+
+    let enemy_techs = read_state.randomizer.encounters.enemy_techs;
+    let enemy_tech_pool = read_state.randomizer.encounters.enemy_tech_pool;
+
+    let resistances = read_state.randomizer.encounters.resistances;
+    let min_resistance = read_state.randomizer.encounters.min_resistance;
+    let max_resistance = read_state.randomizer.encounters.max_resistance;
+    let keep_total_resistance = read_state.randomizer.encounters.keep_total_resistance;
+    let single_weakness = read_state.randomizer.encounters.single_weakness;
+
+    let errors = read_state.validate();
+
     rsx! {
         div {
             class: "segment",
@@ -45,16 +65,49 @@ pub fn encounters() -> Element {
                         state.write().randomizer.encounters.cardmon = x;
                     }
                 },
-                checkbox::checkbox {
-                    label: "Bosses",
-                    id: "encounters.bosses",
-                    checked: bosses,
-                    disabled: !enabled,
-                    tooltip: "Shuffle bosses",
-                    onchange: move |x: bool| {
-                        state.write().randomizer.encounters.bosses = x;
+            },
+            div {
+                class: "left",
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        r#style: "max-width: 300px; width: 300px;",
+                        "Boss strategy"
+                        br {},
+                        "Keep -> don't move bosses"
+                        br {},
+                        "Mixed -> bosses are shuffled with regular encounters",
+                        br {},
+                        "Separate -> bosses are shuffled among bosses, rescaled to the fight they replace, Galacticmon and Zanbamon stay in place"
+                    },
+                    label {
+                        r#for: "encounters.bosses",
+                        "Bosses"
+                    },
+                    select {
+                        id: "encounters.bosses",
+                        disabled: !enabled,
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.encounters.bosses = BossStrategy::from(x.data.value().parse::<u8>().unwrap_or(0));
+                        },
+                        option {
+                            value: "0",
+                            selected: bosses == BossStrategy::Keep,
+                            "Keep"
+                        },
+                        option {
+                            value: "1",
+                            selected: bosses == BossStrategy::Mixed,
+                            "Mixed"
+                        },
+                        option {
+                            value: "2",
+                            selected: bosses == BossStrategy::Separate,
+                            "Separate"
+                        },
                     }
-                },
+                }
             },
             div {
                 class: "left",
@@ -106,6 +159,221 @@ pub fn encounters() -> Element {
                     }
                 }
             },
+            div {
+                class: "left",
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        r#style: "max-width: 300px; width: 300px;",
+                        "Encounter tiers"
+                        br {},
+                        "Global -> any digimon can replace any encounter"
+                        br {},
+                        "Stage -> replaced by digimon of the same stage (Rookie/Champion/Ultimate/Mega)",
+                        br {},
+                        "Level -> replaced by encounters in the same original level band"
+                    },
+                    label {
+                        r#for: "encounters.tier",
+                        "Tiers"
+                    },
+                    select {
+                        id: "encounters.tier",
+                        disabled: !enabled,
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.encounters.tier = EncounterTier::from(x.data.value().parse::<u8>().unwrap_or(0));
+                        },
+                        option {
+                            value: "0",
+                            selected: tier == EncounterTier::Global,
+                            "Global"
+                        },
+                        option {
+                            value: "1",
+                            selected: tier == EncounterTier::Stage,
+                            "Stage"
+                        },
+                        option {
+                            value: "2",
+                            selected: tier == EncounterTier::Level,
+                            "Level"
+                        },
+                    }
+                },
+                number_field::number_field {
+                    id: "encounters.level_band",
+                    invalid: invalid(&errors, "randomizer.encounters.levelBand"),
+                    label: "Level band",
+                    disabled: !enabled || tier == EncounterTier::Global,
+                    tooltip: "Width of a level band (also used for enemy only digimon in Stage)",
+                    onchange: move |x: i64| {
+                        state.write().randomizer.encounters.level_band = x as u16;
+                    },
+                    value: level_band as i64,
+                    min: 1,
+                    max: 99
+                },
+            },
+            div {
+                class: "left",
+                checkbox::checkbox {
+                    label: "Teams",
+                    id: "encounters.teams",
+                    checked: teams,
+                    disabled: !enabled,
+                    tooltip: "Rebuild enemy teams (bosses and scripted fights keep theirs)",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.encounters.teams = x;
+                    }
+                },
+                number_field::number_field {
+                    id: "encounters.min_team_size",
+                    invalid: invalid(&errors, "randomizer.encounters.minTeamSize"),
+                    label: "Min size",
+                    disabled: !enabled || !teams,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.encounters.min_team_size = x as u8;
+                    },
+                    value: min_team_size as i64,
+                    min: 1,
+                    max: max_team_size as i64
+                },
+                number_field::number_field {
+                    id: "encounters.max_team_size",
+                    invalid: invalid(&errors, "randomizer.encounters.maxTeamSize"),
+                    label: "Max size",
+                    disabled: !enabled || !teams,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.encounters.max_team_size = x as u8;
+                    },
+                    value: max_team_size as i64,
+                    min: min_team_size as i64,
+                    max: MAX_TEAM_SIZE as i64
+                },
+                label {
+                    r#for: "encounters.team_theme",
+                    "Theme"
+                },
+                select {
+                    id: "encounters.team_theme",
+                    disabled: !enabled || !teams,
+                    onchange: move |x: Event<FormData>| {
+                        state.write().randomizer.encounters.team_theme = TeamTheme::from(x.data.value().parse::<u8>().unwrap_or(0));
+                    },
+                    option {
+                        value: "0",
+                        selected: team_theme == TeamTheme::Random,
+                        "Random"
+                    },
+                    option {
+                        value: "1",
+                        selected: team_theme == TeamTheme::Element,
+                        "Element"
+                    },
+                    option {
+                        value: "2",
+                        selected: team_theme == TeamTheme::Stage,
+                        "Stage"
+                    },
+                }
+            },
+            div {
+                class: "left",
+                checkbox::checkbox {
+                    label: "Enemy techs",
+                    id: "encounters.enemy_techs",
+                    checked: enemy_techs,
+                    disabled: !enabled,
+                    tooltip: "Give enemies a random attack out of every damaging tech in the pool that the party can't learn (bosses keep theirs), power is adjusted to the replaced tech",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.encounters.enemy_techs = x;
+                    }
+                },
+                label {
+                    r#for: "encounters.enemy_tech_pool",
+                    "Pool"
+                },
+                select {
+                    id: "encounters.enemy_tech_pool",
+                    disabled: !enabled || !enemy_techs,
+                    onchange: move |x: Event<FormData>| {
+                        state.write().randomizer.encounters.enemy_tech_pool = TechPool::from(x.data.value().parse::<u8>().unwrap_or(0));
+                    },
+                    option {
+                        value: "0",
+                        selected: enemy_tech_pool == TechPool::Element,
+                        "Same element"
+                    },
+                    option {
+                        value: "1",
+                        selected: enemy_tech_pool == TechPool::HitEffect,
+                        "Same hit effect"
+                    },
+                    option {
+                        value: "2",
+                        selected: enemy_tech_pool == TechPool::Any,
+                        "Any"
+                    },
+                }
+            },
+            div {
+                class: "left",
+                checkbox::checkbox {
+                    label: "Resistances",
+                    id: "encounters.resistances",
+                    checked: resistances,
+                    disabled: !enabled,
+                    tooltip: "Redistribute enemy resistances",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.encounters.resistances = x;
+                    }
+                },
+                number_field::number_field {
+                    id: "encounters.min_resistance",
+                    invalid: invalid(&errors, "randomizer.encounters.minResistance"),
+                    label: "Min",
+                    disabled: !enabled || !resistances,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.encounters.min_resistance = x as i16;
+                    },
+                    value: min_resistance as i64,
+                    min: 0,
+                    max: max_resistance as i64
+                },
+                number_field::number_field {
+                    id: "encounters.max_resistance",
+                    invalid: invalid(&errors, "randomizer.encounters.maxResistance"),
+                    label: "Max",
+                    disabled: !enabled || !resistances,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.encounters.max_resistance = x as i16;
+                    },
+                    value: max_resistance as i64,
+                    min: min_resistance as i64,
+                    max: MAX_RESISTANCE as i64
+                },
+                checkbox::checkbox {
+                    label: "Keep total",
+                    id: "encounters.keep_total_resistance",
+                    checked: keep_total_resistance,
+                    disabled: !enabled || !resistances,
+                    tooltip: "Keep the sum of an enemy's resistances",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.encounters.keep_total_resistance = x;
+                    }
+                },
+                checkbox::checkbox {
+                    label: "Single weakness",
+                    id: "encounters.single_weakness",
+                    checked: single_weakness,
+                    disabled: !enabled || !resistances,
+                    tooltip: "One resistance at min, the others in the upper half of the range",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.encounters.single_weakness = x;
+                    }
+                },
+            },
             checkbox::checkbox {
                 label: "Keep Zanbamon",
                 id: "encounters.keep_zanbamon",
@@ -0,0 +1,221 @@
+use dioxus::prelude::*;
+
+use crate::json::{invalid, ItemCategory, ItemPool, ItemRef, Preset, ValidationError};
+
+// comma separated ids (decimal or 0x hex) and item names
+pub fn from_text(text: &str) -> Vec<ItemRef> {
+    text.split(',')
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .map(|x| {
+            let id = match x.strip_prefix("0x") {
+                Some(hex) => u16::from_str_radix(hex, 16).ok(),
+                None => x.parse::<u16>().ok(),
+            };
+
+            match id {
+                Some(id) => ItemRef::Id(id),
+                None => ItemRef::Name(String::from(x)),
+            }
+        })
+        .collect()
+}
+
+pub fn to_text(refs: &[ItemRef]) -> String {
+    let items: Vec<String> = refs
+        .iter()
+        .map(|x| match x {
+            ItemRef::Id(id) => format!("{id:#x}"),
+            ItemRef::Name(name) => name.clone(),
+        })
+        .collect();
+
+    items.join(", ")
+}
+
+// class for inputs that aren't number fields
+pub fn invalid_class(errors: &[ValidationError], path: &str) -> &'static str {
+    match invalid(errors, path) {
+        true => "invalid",
+        false => "",
+    }
+}
+
+fn set_category(categories: &mut Vec<ItemCategory>, category: ItemCategory, enabled: bool) {
+    categories.retain(|x| *x != category);
+
+    if enabled {
+        categories.push(category);
+    }
+}
+
+#[component]
+pub fn item_pools() -> Element {
+    let mut state = use_context::<Signal<Preset>>();
+    let read_state = state();
+    let errors = read_state.validate();
+
+    let item_pools = read_state.randomizer.item_pools.clone();
+
+    rsx! {
+        div {
+            class: "segment",
+            div {
+                class: "tooltip",
+                span {
+                    class: "tooltiptext",
+                    style: "width: 250px;",
+                    "Named pools usable by shops, item boxes, auctions and drops",
+                    br {},
+                    "Items of any checked category plus include, minus exclude",
+                    br {},
+                    "Stocked / Dropped -> sold in a vanilla shop / dropped by a vanilla enemy",
+                    br {},
+                    "Include / exclude take ids (decimal or 0x hex) and item names",
+                },
+                label {
+                    "Item Pools"
+                },
+            }
+            for (i, pool) in item_pools.into_iter().enumerate() {
+                div {
+                    class: "left",
+                    input {
+                        id: "item_pools.{i}.name",
+                        r#type: "text",
+                        class: invalid_class(&errors, &format!("randomizer.itemPools.{i}.name")),
+                        value: "{pool.name}",
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.item_pools[i].name = x.data.value();
+                        }
+                    },
+                    label {
+                        r#for: "item_pools.{i}.buyable",
+                        "Buyable"
+                    },
+                    input {
+                        id: "item_pools.{i}.buyable",
+                        r#type: "checkbox",
+                        r#checked: pool.categories.contains(&ItemCategory::Buyable),
+                        onchange: move |x: Event<FormData>| {
+                            set_category(&mut state.write().randomizer.item_pools[i].categories, ItemCategory::Buyable, x.data.value() == "true");
+                        }
+                    },
+                    label {
+                        r#for: "item_pools.{i}.sellable",
+                        "Sellable"
+                    },
+                    input {
+                        id: "item_pools.{i}.sellable",
+                        r#type: "checkbox",
+                        r#checked: pool.categories.contains(&ItemCategory::Sellable),
+                        onchange: move |x: Event<FormData>| {
+                            set_category(&mut state.write().randomizer.item_pools[i].categories, ItemCategory::Sellable, x.data.value() == "true");
+                        }
+                    },
+                    label {
+                        r#for: "item_pools.{i}.stocked",
+                        "Stocked"
+                    },
+                    input {
+                        id: "item_pools.{i}.stocked",
+                        r#type: "checkbox",
+                        r#checked: pool.categories.contains(&ItemCategory::Stocked),
+                        onchange: move |x: Event<FormData>| {
+                            set_category(&mut state.write().randomizer.item_pools[i].categories, ItemCategory::Stocked, x.data.value() == "true");
+                        }
+                    },
+                    label {
+                        r#for: "item_pools.{i}.dropped",
+                        "Dropped"
+                    },
+                    input {
+                        id: "item_pools.{i}.dropped",
+                        r#type: "checkbox",
+                        r#checked: pool.categories.contains(&ItemCategory::Dropped),
+                        onchange: move |x: Event<FormData>| {
+                            set_category(&mut state.write().randomizer.item_pools[i].categories, ItemCategory::Dropped, x.data.value() == "true");
+                        }
+                    },
+                    label {
+                        r#for: "item_pools.{i}.include",
+                        "Include"
+                    },
+                    input {
+                        id: "item_pools.{i}.include",
+                        r#type: "text",
+                        class: invalid_class(&errors, &format!("randomizer.itemPools.{i}.include")),
+                        value: "{to_text(&pool.include)}",
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.item_pools[i].include = from_text(&x.data.value());
+                        }
+                    },
+                    label {
+                        r#for: "item_pools.{i}.exclude",
+                        "Exclude"
+                    },
+                    input {
+                        id: "item_pools.{i}.exclude",
+                        r#type: "text",
+                        class: invalid_class(&errors, &format!("randomizer.itemPools.{i}.exclude")),
+                        value: "{to_text(&pool.exclude)}",
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.item_pools[i].exclude = from_text(&x.data.value());
+                        }
+                    },
+                    input {
+                        r#type: "button",
+                        value: "Remove",
+                        onclick: move |_| {
+                            state.write().randomizer.item_pools.remove(i);
+                        }
+                    },
+                }
+            }
+            div {
+                class: "left",
+                input {
+                    r#type: "button",
+                    value: "Add pool",
+                    onclick: move |_| {
+                        let mut preset = state.write();
+                        let pools = &preset.randomizer.item_pools;
+                        let name = (1..)
+                            .map(|x| format!("pool {x}"))
+                            .find(|name| !pools.iter().any(|x| x.name == *name))
+                            .unwrap();
+
+                        preset.randomizer.item_pools.push(ItemPool {
+                            name,
+                            categories: vec![ItemCategory::Sellable],
+                            include: Vec::new(),
+                            exclude: Vec::new(),
+                        });
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trip() {
+        let refs = from_text("0x5a, 62 ,Runner Shoes,, 0xzz");
+
+        assert_eq!(
+            refs,
+            vec![
+                ItemRef::Id(0x5a),
+                ItemRef::Id(62),
+                ItemRef::Name(String::from("Runner Shoes")),
+                ItemRef::Name(String::from("0xzz")),
+            ]
+        );
+        assert_eq!(to_text(&refs), "0x5a, 0x3e, Runner Shoes, 0xzz");
+        assert_eq!(from_text(&to_text(&refs)), refs);
+    }
+}
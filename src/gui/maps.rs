@@ -1,12 +1,13 @@
 use dioxus::prelude::*;
 
-use crate::gui::checkbox;
+use crate::gui::{checkbox, item_pools::invalid_class};
 use crate::json::{GroupStrategy, MusicPool, Preset, ShopItems};
 
 #[component]
 pub fn maps() -> Element {
     let mut state = use_context::<Signal<Preset>>();
     let read_state = state();
+    let errors = read_state.validate();
 
     let enabled = read_state.randomizer.maps.enabled;
 
@@ -23,6 +24,7 @@ pub fn maps() -> Element {
     let selected_group_strategy = read_state.randomizer.maps.group_strategy;
 
     let selected = read_state.randomizer.maps.item_boxes_items_only.clone();
+    let item_pools = read_state.randomizer.item_pools.clone();
 
     let music = read_state.randomizer.maps.music;
     let selected_music_pool = read_state.randomizer.maps.music_pool.clone();
@@ -146,9 +148,10 @@ pub fn maps() -> Element {
                 },
                 select {
                     id: "maps.item_boxes_items_only",
+                    class: invalid_class(&errors, "randomizer.maps.itemBoxesItemsOnly"),
                     disabled: !enabled,
                     onchange: move |x: Event<FormData>| {
-                        state.write().randomizer.maps.item_boxes_items_only = ShopItems::from(x.data.value().parse::<u8>().unwrap_or(0));
+                        state.write().randomizer.maps.item_boxes_items_only = ShopItems::from_value(&x.data.value());
                     },
                     option {
                         value: "0",
@@ -165,7 +168,14 @@ pub fn maps() -> Element {
                         selected: selected == ShopItems::Ironmon,
                         "Ironmon"
                     },
-                }
+                    for pool in item_pools.iter() {
+                        option {
+                            value: "custom:{pool.name}",
+                            selected: selected == ShopItems::Custom(pool.name.clone()),
+                            "{pool.name}"
+                        },
+                    }
+                },
             }
             div {
                 class: "left",
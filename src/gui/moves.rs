@@ -0,0 +1,110 @@
+use dioxus::prelude::*;
+
+use crate::{
+    gui::{checkbox, number_field},
+    json::{invalid, Preset},
+    rand::MAX_HITS,
+};
+
+#[component]
+pub fn moves() -> Element {
+    let mut state = use_context::<Signal<Preset>>();
+    let read_state = state();
+    let errors = read_state.validate();
+
+    let enabled = read_state.randomizer.moves.enabled;
+    let power_variance = read_state.randomizer.moves.power_variance as i64;
+    let mp_from_power = read_state.randomizer.moves.mp_from_power;
+    let elements = read_state.randomizer.moves.elements;
+
+    let multi_hits = read_state.randomizer.moves.multi_hits;
+    let min_hits = read_state.randomizer.moves.min_hits as i64;
+    let max_hits = read_state.randomizer.moves.max_hits as i64;
+
+    rsx! {
+        div {
+            class: "segment",
+            checkbox::checkbox {
+                label: "Techs",
+                id: "moves.enabled",
+                checked: enabled,
+                tooltip: "Randomize tech power, MP cost, elements and hit counts",
+                onchange: move |x: bool| {
+                    state.write().randomizer.moves.enabled = x;
+                }
+            }
+            div {
+                class: "left",
+                number_field::number_field {
+                    id: "moves.power_variance",
+                    invalid: invalid(&errors, "randomizer.moves.powerVariance"),
+                    label: "Power variance %",
+                    disabled: !enabled,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.moves.power_variance = x as u8;
+                    },
+                    value: power_variance,
+                    min: 0,
+                    max: 100
+                },
+                checkbox::checkbox {
+                    id: "moves.mp_from_power",
+                    label: "MP from power",
+                    disabled: !enabled,
+                    checked: mp_from_power,
+                    tooltip: "Scale MP cost with the new power",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.moves.mp_from_power = x;
+                    },
+                },
+                checkbox::checkbox {
+                    id: "moves.elements",
+                    label: "Elements",
+                    disabled: !enabled,
+                    checked: elements,
+                    tooltip: "Shuffle elements between techs",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.moves.elements = x;
+                    },
+                },
+            }
+            div {
+                class: "left",
+                checkbox::checkbox {
+                    id: "moves.multi_hits",
+                    label: "Multi hits",
+                    disabled: !enabled,
+                    checked: multi_hits,
+                    tooltip: "Randomize hit counts of multi hit techs, total damage stays within the power variance",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.moves.multi_hits = x;
+                    },
+                },
+                number_field::number_field {
+                    id: "moves.min_hits",
+                    invalid: invalid(&errors, "randomizer.moves.minHits"),
+                    label: "Min",
+                    disabled: !enabled || !multi_hits,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.moves.min_hits = x as u8;
+                    },
+                    value: min_hits,
+                    min: 2,
+                    max: max_hits
+                },
+                number_field::number_field {
+                    id: "moves.max_hits",
+                    invalid: invalid(&errors, "randomizer.moves.maxHits"),
+                    label: "Max",
+                    disabled: !enabled || !multi_hits,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.moves.max_hits = x as u8;
+                    },
+                    value: max_hits,
+                    min: min_hits,
+                    max: MAX_HITS as i64
+                },
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ pub fn number_field(
     label: &'static str,
     id: &'static str,
     #[props(default = false)] disabled: bool,
+    #[props(default = false)] invalid: bool,
     #[props(default = 1)] step: i64,
     onchange: EventHandler<i64>,
     tooltip: Option<&'static str>,
@@ -17,6 +18,11 @@ pub fn number_field(
         None => "",
     };
 
+    let input_class = match invalid {
+        true => "short_number invalid",
+        false => "short_number",
+    };
+
     rsx! {
         div {
             class: class,
@@ -33,7 +39,7 @@ pub fn number_field(
             input {
                 r#type: "number",
                 value: value,
-                class: "short_number",
+                class: input_class,
                 min: min,
                 max: max,
                 step: step,
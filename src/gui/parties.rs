@@ -1,6 +1,6 @@
 use dioxus::prelude::*;
 
-use crate::json::Preset;
+use crate::json::{invalid, AffinityMode, DvRequirements, PartyMode, Preset};
 
 use crate::gui::{checkbox, number_field};
 
@@ -8,9 +8,13 @@ use crate::gui::{checkbox, number_field};
 pub fn parties() -> Element {
     let mut state = use_context::<Signal<Preset>>();
     let read_state = state();
+    let errors = read_state.validate();
 
     let enabled = read_state.randomizer.parties.enabled;
     let random_parties = read_state.randomizer.parties.parties;
+    let party_mode = read_state.randomizer.parties.party_mode;
+    let starting_rookie = read_state.randomizer.parties.starting_rookie;
+    let random_previews = read_state.randomizer.parties.random_previews;
 
     let stat_distribution = read_state.randomizer.parties.stat_distribution;
     let min_stat = read_state.randomizer.parties.min_starting_stat;
@@ -20,11 +24,32 @@ pub fn parties() -> Element {
     let min_res = read_state.randomizer.parties.min_starting_res;
     let total_start_res = read_state.randomizer.parties.total_starting_res;
 
+    let dv_stat_distribution = read_state.randomizer.parties.dv_stat_distribution;
+    let dv_total_stats = read_state.randomizer.parties.dv_total_stats;
+    let dv_min_stat = read_state.randomizer.parties.dv_min_stat;
+    let dv_res_distribution = read_state.randomizer.parties.dv_res_distribution;
+    let dv_total_res = read_state.randomizer.parties.dv_total_res;
+    let dv_min_res = read_state.randomizer.parties.dv_min_res;
+    let dv_stage_scaling = read_state.randomizer.parties.dv_stage_scaling;
+
+    let affinities = read_state.randomizer.parties.affinities;
+    let min_affinity = read_state.randomizer.parties.min_affinity;
+    let max_affinity = read_state.randomizer.parties.max_affinity;
+
     let learned_tech = read_state.randomizer.parties.learned_tech;
+    let learned_tech_tier = read_state.randomizer.parties.learned_tech_tier;
+    let learned_tech_element = read_state.randomizer.parties.learned_tech_element;
+    let learned_tech_attack = read_state.randomizer.parties.learned_tech_attack;
     let signatures = read_state.randomizer.parties.signatures;
 
     let digivolutions = read_state.randomizer.parties.digivolutions;
     let keep_stages = read_state.randomizer.parties.keep_stages;
+    let shuffle_trees = read_state.randomizer.parties.shuffle_trees;
+
+    let requirements = read_state.randomizer.parties.requirements;
+    let min_requirement_level = read_state.randomizer.parties.min_requirement_level;
+    let max_requirement_level = read_state.randomizer.parties.max_requirement_level;
+    let easy_requirement_percent = read_state.randomizer.parties.easy_requirement_percent;
 
     let exp_modifier = read_state.randomizer.parties.exp_modifier;
     let min_exp_mod = read_state.randomizer.parties.min_exp_modifier;
@@ -69,6 +94,79 @@ pub fn parties() -> Element {
                     }
                 },
             },
+            div {
+                class: "left",
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        r#style: "max-width: 300px; width: 300px;",
+                        "Party mode"
+                        br {},
+                        "Random -> shuffle rookies between packs",
+                        br {},
+                        "Fixed -> every pack starts with the chosen rookie",
+                        br {},
+                        "Random leader -> every pack starts with the same random rookie, the other two stay shuffled",
+                        br {},
+                        "Single -> every pack is only one random rookie"
+                    },
+                    label {
+                        r#for: "parties.party_mode",
+                        "Mode"
+                    },
+                    select {
+                        id: "parties.party_mode",
+                        disabled: !enabled || !random_parties,
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.parties.party_mode = PartyMode::from(x.data.value().parse::<u8>().unwrap_or(0));
+                        },
+                        option {
+                            value: "0",
+                            selected: party_mode == PartyMode::Random,
+                            "Random"
+                        },
+                        option {
+                            value: "1",
+                            selected: party_mode == PartyMode::Fixed,
+                            "Fixed"
+                        },
+                        option {
+                            value: "2",
+                            selected: party_mode == PartyMode::RandomLeader,
+                            "Random leader"
+                        },
+                        option {
+                            value: "3",
+                            selected: party_mode == PartyMode::Single,
+                            "Single"
+                        },
+                    }
+                },
+                number_field::number_field {
+                    label: "Rookie",
+                    id: "parties.starting_rookie",
+                    invalid: invalid(&errors, "randomizer.parties.startingRookie"),
+                    value: starting_rookie as i64,
+                    disabled: !enabled || !random_parties || party_mode != PartyMode::Fixed,
+                    min: 0,
+                    max: dmw3_consts::ROOKIE_COUNT as i64 - 1,
+                    tooltip: "Index of the starting rookie",
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.starting_rookie = x as u8
+                    }
+                },
+                checkbox::checkbox {
+                    label: "Random Previews",
+                    id: "parties.random_previews",
+                    checked: random_previews,
+                    disabled: !enabled || !random_parties,
+                    tooltip: "Pack previews don't show the actual packs",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.parties.random_previews = x;
+                    }
+                }
+            },
             div {
                 class: "left",
                 checkbox::checkbox {
@@ -84,6 +182,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Min stat",
                     id: "parties.min_stat",
+                    invalid: invalid(&errors, "randomizer.parties.minStartingStat"),
                     value: min_stat as i64,
                     disabled: !stat_distribution || !enabled,
                     min: 1,
@@ -96,6 +195,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Total stats",
                     id: "parties.total_stats",
+                    invalid: invalid(&errors, "randomizer.parties.totalStartingStats"),
                     value: total_start_stat as i64,
                     disabled: !stat_distribution || !enabled,
                     min: (min_stat * 5) as i64,
@@ -121,6 +221,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Min res",
                     id: "parties.min_res",
+                    invalid: invalid(&errors, "randomizer.parties.minStartingRes"),
                     value: min_res as i64,
                     disabled: !res_distribution || !enabled,
                     min: 1,
@@ -133,6 +234,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Total res",
                     id: "parties.total_res",
+                    invalid: invalid(&errors, "randomizer.parties.totalStartingRes"),
                     value: total_start_res as i64,
                     disabled: !res_distribution || !enabled,
                     min: (min_res * 7) as i64,
@@ -143,6 +245,164 @@ pub fn parties() -> Element {
                     }
                 }
             },
+            div {
+                class: "left",
+                checkbox::checkbox {
+                    label: "DV stat distribution",
+                    id: "parties.dv_stat_distribution",
+                    checked: dv_stat_distribution,
+                    disabled: !enabled,
+                    tooltip: "Randomize digivolution stat bonuses",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.parties.dv_stat_distribution = x;
+                    }
+                },
+                number_field::number_field {
+                    label: "Min stat",
+                    id: "parties.dv_min_stat",
+                    invalid: invalid(&errors, "randomizer.parties.dvMinStat"),
+                    value: dv_min_stat as i64,
+                    disabled: !dv_stat_distribution || !enabled,
+                    min: 0,
+                    max: (dv_total_stats / 5) as i64,
+                    tooltip: "Mininum bonus per stat",
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.dv_min_stat = x as u16
+                    }
+                },
+                number_field::number_field {
+                    label: "Total stats",
+                    id: "parties.dv_total_stats",
+                    invalid: invalid(&errors, "randomizer.parties.dvTotalStats"),
+                    value: dv_total_stats as i64,
+                    disabled: !dv_stat_distribution || !enabled,
+                    min: (dv_min_stat * 5) as i64,
+                    max: 4995,
+                    tooltip: "Total champion stat bonuses",
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.dv_total_stats = x as u16
+                    }
+                }
+            },
+            div {
+                class: "left",
+                checkbox::checkbox {
+                    label: "DV res distribution",
+                    id: "parties.dv_res_distribution",
+                    checked: dv_res_distribution,
+                    disabled: !enabled,
+                    tooltip: "Randomize digivolution res bonuses",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.parties.dv_res_distribution = x;
+                    }
+                },
+                number_field::number_field {
+                    label: "Min res",
+                    id: "parties.dv_min_res",
+                    invalid: invalid(&errors, "randomizer.parties.dvMinRes"),
+                    value: dv_min_res as i64,
+                    disabled: !dv_res_distribution || !enabled,
+                    min: 0,
+                    max: (dv_total_res / 7) as i64,
+                    tooltip: "Mininum bonus per res",
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.dv_min_res = x as u16
+                    }
+                },
+                number_field::number_field {
+                    label: "Total res",
+                    id: "parties.dv_total_res",
+                    invalid: invalid(&errors, "randomizer.parties.dvTotalRes"),
+                    value: dv_total_res as i64,
+                    disabled: !dv_res_distribution || !enabled,
+                    min: (dv_min_res * 7) as i64,
+                    max: 4995,
+                    tooltip: "Total champion res bonuses",
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.dv_total_res = x as u16
+                    }
+                },
+                number_field::number_field {
+                    label: "Stage scaling %",
+                    id: "parties.dv_stage_scaling",
+                    invalid: invalid(&errors, "randomizer.parties.dvStageScaling"),
+                    value: dv_stage_scaling as i64,
+                    disabled: (!dv_stat_distribution && !dv_res_distribution) || !enabled,
+                    min: 0,
+                    max: 1000,
+                    tooltip: "Extra budget per stage after champion",
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.dv_stage_scaling = x as u16
+                    }
+                }
+            },
+            div {
+                class: "left",
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        r#style: "max-width: 300px; width: 300px;",
+                        "Stat and res affinities"
+                        br {},
+                        "Linked -> follow the randomized base stats",
+                        br {},
+                        "Random -> rolled separately from base stats",
+                        br {},
+                        "Vanilla -> keep vanilla affinities"
+                    },
+                    label {
+                        r#for: "parties.affinities",
+                        "Affinities"
+                    },
+                    select {
+                        id: "parties.affinities",
+                        disabled: !enabled,
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.parties.affinities = AffinityMode::from(x.data.value().parse::<u8>().unwrap_or(0));
+                        },
+                        option {
+                            value: "0",
+                            selected: affinities == AffinityMode::Linked,
+                            "Linked"
+                        },
+                        option {
+                            value: "1",
+                            selected: affinities == AffinityMode::Random,
+                            "Random"
+                        },
+                        option {
+                            value: "2",
+                            selected: affinities == AffinityMode::Vanilla,
+                            "Vanilla"
+                        },
+                    }
+                },
+                number_field::number_field {
+                    label: "Min affinity",
+                    id: "parties.min_affinity",
+                    invalid: invalid(&errors, "randomizer.parties.minAffinity"),
+                    value: min_affinity as i64,
+                    disabled: !enabled || affinities != AffinityMode::Random,
+                    min: dmw3_consts::MIN_STAT_AFFINITY as i64,
+                    max: max_affinity as i64,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.min_affinity = x as u8
+                    }
+                },
+                number_field::number_field {
+                    label: "Max affinity",
+                    id: "parties.max_affinity",
+                    invalid: invalid(&errors, "randomizer.parties.maxAffinity"),
+                    value: max_affinity as i64,
+                    disabled: !enabled || affinities != AffinityMode::Random,
+                    min: min_affinity as i64,
+                    max: dmw3_consts::MAX_STAT_AFFINITY as i64,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.max_affinity = x as u8
+                    }
+                }
+            },
             div {
                 class: "left",
                 checkbox::checkbox {
@@ -155,6 +415,36 @@ pub fn parties() -> Element {
                         state.write().randomizer.parties.learned_tech = x;
                     }
                 },
+                checkbox::checkbox {
+                    label: "Same Tier",
+                    checked: learned_tech_tier,
+                    id: "parties.learned_tech_tier",
+                    disabled: !enabled || !learned_tech,
+                    tooltip: "Favor techs of the same power tier as the replaced tech",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.parties.learned_tech_tier = x;
+                    }
+                },
+                checkbox::checkbox {
+                    label: "Same Element",
+                    checked: learned_tech_element,
+                    id: "parties.learned_tech_element",
+                    disabled: !enabled || !learned_tech,
+                    tooltip: "Favor techs of an element the digivolution originally learns",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.parties.learned_tech_element = x;
+                    }
+                },
+                checkbox::checkbox {
+                    label: "Guarantee Attack",
+                    checked: learned_tech_attack,
+                    id: "parties.learned_tech_attack",
+                    disabled: !enabled || !learned_tech,
+                    tooltip: "Every digivolution learns at least one attacking tech",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.parties.learned_tech_attack = x;
+                    }
+                },
                 checkbox::checkbox {
                     label: "Signatures",
                     checked: signatures,
@@ -187,6 +477,96 @@ pub fn parties() -> Element {
                     onchange: move |x: bool| {
                         state.write().randomizer.parties.keep_stages = x;
                     }
+                },
+                checkbox::checkbox {
+                    label: "Shuffle Trees",
+                    checked: shuffle_trees,
+                    id: "parties.shuffle_trees",
+                    disabled: !enabled || !digivolutions,
+                    tooltip: "Build every rookie a new tree from the digivolution requirements of all rookies, keeps stages",
+                    onchange: move |x: bool| {
+                        state.write().randomizer.parties.shuffle_trees = x;
+                    }
+                }
+            },
+            div {
+                class: "left",
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        r#style: "max-width: 300px; width: 300px;",
+                        "Digivolution requirements"
+                        br {},
+                        "Keep -> vanilla levels and prerequisites",
+                        br {},
+                        "Random -> random levels and prerequisites of the same stage",
+                        br {},
+                        "Easy -> vanilla requirements with lowered levels"
+                    },
+                    label {
+                        r#for: "parties.requirements",
+                        "Requirements"
+                    },
+                    select {
+                        id: "parties.requirements",
+                        disabled: !enabled,
+                        onchange: move |x: Event<FormData>| {
+                            state.write().randomizer.parties.requirements = DvRequirements::from(x.data.value().parse::<u8>().unwrap_or(0));
+                        },
+                        option {
+                            value: "0",
+                            selected: requirements == DvRequirements::Keep,
+                            "Keep"
+                        },
+                        option {
+                            value: "1",
+                            selected: requirements == DvRequirements::Random,
+                            "Random"
+                        },
+                        option {
+                            value: "2",
+                            selected: requirements == DvRequirements::Easy,
+                            "Easy"
+                        },
+                    }
+                },
+                number_field::number_field {
+                    label: "Min level",
+                    id: "parties.min_requirement_level",
+                    invalid: invalid(&errors, "randomizer.parties.minRequirementLevel"),
+                    value: min_requirement_level as i64,
+                    disabled: !enabled || requirements != DvRequirements::Random,
+                    min: 1,
+                    max: max_requirement_level as i64,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.min_requirement_level = x as u16
+                    }
+                },
+                number_field::number_field {
+                    label: "Max level",
+                    id: "parties.max_requirement_level",
+                    invalid: invalid(&errors, "randomizer.parties.maxRequirementLevel"),
+                    value: max_requirement_level as i64,
+                    disabled: !enabled || requirements != DvRequirements::Random,
+                    min: min_requirement_level as i64,
+                    max: 99,
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.max_requirement_level = x as u16
+                    }
+                },
+                number_field::number_field {
+                    label: "Easy %",
+                    id: "parties.easy_requirement_percent",
+                    invalid: invalid(&errors, "randomizer.parties.easyRequirementPercent"),
+                    value: easy_requirement_percent as i64,
+                    disabled: !enabled || requirements != DvRequirements::Easy,
+                    min: 1,
+                    max: 100,
+                    tooltip: "Requirement levels are lowered to this percentage",
+                    onchange: move |x: i64| {
+                        state.write().randomizer.parties.easy_requirement_percent = x as u16
+                    }
                 }
             },
             div {
@@ -211,6 +591,7 @@ pub fn parties() -> Element {
                         number_field::number_field {
                             label: "Min",
                             id: "parties.min_exp_modifier",
+                            invalid: invalid(&errors, "randomizer.parties.minExpModifier"),
                             value: min_exp_mod as i64,
                             disabled: !exp_modifier|| !enabled,
                             min: 1,
@@ -222,6 +603,7 @@ pub fn parties() -> Element {
                         number_field::number_field {
                             label: "Max",
                             id: "parties.max_exp_modifier",
+                            invalid: invalid(&errors, "randomizer.parties.maxExpModifier"),
                             value: max_exp_mod as i64,
                             disabled: !exp_modifier|| !enabled,
                             min: min_exp_mod as i64,
@@ -262,6 +644,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Min",
                     id: "parties.min_starting_hp",
+                    invalid: invalid(&errors, "randomizer.parties.minStartingHp"),
                     value: min_start_hp as i64,
                     disabled: !starting_hp_mp|| !enabled,
                     min: 1,
@@ -273,6 +656,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Max",
                     id: "parties.max_starting_hp",
+                    invalid: invalid(&errors, "randomizer.parties.maxStartingHp"),
                     value: max_start_hp as i64,
                     disabled: !starting_hp_mp|| !enabled,
                     min: min_start_hp as i64,
@@ -288,6 +672,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Min",
                     id: "parties.min_starting_mp",
+                    invalid: invalid(&errors, "randomizer.parties.minStartingMp"),
                     value: min_start_mp as i64,
                     disabled: !starting_hp_mp || !enabled,
                     min: 1,
@@ -299,6 +684,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Max",
                     id: "parties.max_starting_mp",
+                    invalid: invalid(&errors, "randomizer.parties.maxStartingMp"),
                     value: max_start_mp as i64,
                     disabled: !starting_hp_mp || !enabled,
                     min: min_start_mp as i64,
@@ -314,6 +700,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Min",
                     id: "parties.min_hp_modifier",
+                    invalid: invalid(&errors, "randomizer.parties.minHpModifier"),
                     value: min_hp_mod as i64,
                     disabled: !starting_hp_mp || !enabled,
                     min: 1,
@@ -325,6 +712,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Max",
                     id: "parties.max_hp_modifier",
+                    invalid: invalid(&errors, "randomizer.parties.maxHpModifier"),
                     value: max_hp_mod as i64,
                     disabled: !starting_hp_mp || !enabled,
                     min: min_hp_mod as i64,
@@ -340,6 +728,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Min",
                     id: "parties.min_mp_modifier",
+                    invalid: invalid(&errors, "randomizer.parties.minMpModifier"),
                     value: min_mp_mod as i64,
                     disabled: !starting_hp_mp || !enabled,
                     min: 1,
@@ -351,6 +740,7 @@ pub fn parties() -> Element {
                 number_field::number_field {
                     label: "Max",
                     id: "parties.max_mp_modifier",
+                    invalid: invalid(&errors, "randomizer.parties.maxMpModifier"),
                     value: max_mp_mod as i64,
                     disabled: !starting_hp_mp || !enabled,
                     min: min_mp_mod as i64,
@@ -3,21 +3,28 @@ use std::{fs::File, io::Write};
 use crate::{
     cli::Arguments,
     db::{self},
-    json::Preset,
+    json::{self, Preset},
 };
 
 use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
 use dioxus::prelude::*;
 
-pub type HistoryMapped = (i64, NaiveDateTime, u64, Preset, Preset);
+pub type HistoryMapped = (i64, NaiveDateTime, u64, Preset, Preset, String);
 
 pub fn get_mapped() -> Vec<HistoryMapped> {
     let history = db::history().unwrap_or(Vec::new());
 
     let history_mapped: Vec<_> = history
         .iter()
-        .map(|history| {
-            let preset: Preset = serde_json::from_str(&history.preset).unwrap();
+        .filter_map(|history| {
+            let preset = match json::migrate(&history.preset) {
+                Ok(migrated) => migrated.preset,
+                Err(err) => {
+                    println!("skipping history entry {}: {}", history.created_at, err);
+                    return None;
+                }
+            };
+
             let timestamp = Local
                 .from_utc_datetime(
                     &DateTime::from_timestamp(history.created_at, 0)
@@ -26,13 +33,14 @@ pub fn get_mapped() -> Vec<HistoryMapped> {
                 )
                 .naive_local();
 
-            (
+            Some((
                 history.created_at,
                 timestamp,
                 preset.randomizer.seed,
                 preset.clone(),
                 preset,
-            )
+                history.seed_hash.clone().unwrap_or(String::from("-")),
+            ))
         })
         .collect();
 
@@ -75,6 +83,7 @@ pub fn history() -> Element {
                     tr {
                         th { "Timestamp" },
                         th { "Seed" },
+                        th { "Hash" },
                         th {
                             colspan: 2,
                             ""
@@ -88,6 +97,9 @@ pub fn history() -> Element {
                             td {
                                 "{entry.2}"
                             },
+                            td {
+                                "{entry.5}"
+                            },
                             td {
                                 div {
                                     class: "center",
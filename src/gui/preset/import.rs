@@ -1,4 +1,7 @@
-use crate::{gui::file_upload, json::Preset};
+use crate::{
+    gui::file_upload,
+    json::{self, Preset},
+};
 
 use dioxus::prelude::*;
 
@@ -20,11 +23,20 @@ pub fn import() -> Element {
 
                     match files.first() {
                         Some(file) => {
-                            let json_str = fs::read_to_string(file).unwrap();
+                            let json = fs::read_to_string(file)
+                                .map_err(anyhow::Error::from)
+                                .and_then(|x| json::migrate(x.as_str()));
 
-                            let json: Preset = serde_json::from_str(json_str.as_str()).unwrap();
+                            match json {
+                                Ok(migrated) => {
+                                    for warning in migrated.warnings {
+                                        println!("{}: {}", file, warning);
+                                    }
 
-                            state.set(json);
+                                    state.set(migrated.preset);
+                                },
+                                Err(err) => println!("failed to import preset {}", err),
+                            }
                         },
                         None => {}
                     }
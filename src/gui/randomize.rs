@@ -1,6 +1,12 @@
 use crate::dump::create_spoiler;
 use crate::gui::preset::history::{get_mapped, HistoryMapped};
-use crate::{cli::Arguments, db, json::Preset, mkpsxiso, patch};
+use crate::{
+    cli::Arguments,
+    db, hash,
+    json::{self, Preset},
+    mkpsxiso, patch,
+    workspace::Workspace,
+};
 
 use anyhow::{anyhow, Context};
 use dioxus::prelude::*;
@@ -35,6 +41,7 @@ impl Steps {
 #[component]
 pub fn randomize() -> Element {
     let mut state = use_signal::<Steps>(Steps::default);
+    let mut seed_hash_state = use_signal::<Option<String>>(|| None);
     let args_state = use_context::<Signal<Arguments>>();
     let preset_state = use_context::<Signal<Preset>>();
     let mut history_state = use_context::<Signal<Vec<HistoryMapped>>>();
@@ -44,6 +51,9 @@ pub fn randomize() -> Element {
     let args = args_state();
     let preset = preset_state();
 
+    let errors = preset.validate();
+    let valid = errors.is_empty();
+
     rsx! {
         label {
             r#for: "randomize",
@@ -66,8 +76,9 @@ pub fn randomize() -> Element {
                 to_owned!(preset, args);
                 let current_state = state();
 
-                if !current_state.randomizing() {
+                if valid && !current_state.randomizing() {
                     state.set(Steps::Extracting);
+                    seed_hash_state.set(None);
 
                     spawn(async move {
                         let r: anyhow::Result<()> = async move {
@@ -78,29 +89,33 @@ pub fn randomize() -> Element {
                                 None => preset.randomizer.seed,
                             };
 
-                            db::insert(&preset, &args).map_err(|_| anyhow!("failed to insert to db"))?;
+                            let id = db::insert(&preset, &args).map_err(|_| anyhow!("failed to insert to db"))?;
                             history_state.set(get_mapped());
 
                             let file_name = args.output.unwrap_or(format!("{}", preset.randomizer.seed));
 
-                            mkpsxiso::extract(path).await?;
+                            let workspace = Workspace::new(path).await?;
+
+                            mkpsxiso::extract(&workspace).await?;
 
                             state.set(Steps::Randomizing);
 
-                            let rom_name = path
-                                .file_name()
-                                .context("Failed file name get")?
-                                .to_str()
-                                .context("Failed to_str conversion")?;
+                            let objects = patch(&workspace, &preset).await?;
 
-                            let objects = patch(path, &preset).await?;
+                            let seed_hash = hash::seed_hash(&preset, &objects)?;
 
-                            create_spoiler(&objects, path, file_name.as_str()).await?;
+                            db::set_seed_hash(id, &seed_hash.to_string())?;
+                            history_state.set(get_mapped());
+
+                            create_spoiler(&objects, &workspace, &seed_hash).await?;
 
                             state.set(Steps::Packaging);
 
-                            mkpsxiso::build(rom_name, &file_name).await?;
+                            mkpsxiso::build(&workspace).await?;
+
+                            workspace.publish(&args.output_root, &file_name).await?;
 
+                            seed_hash_state.set(Some(seed_hash.to_string()));
                             state.set(Steps::Input);
 
                             Ok(())
@@ -115,5 +130,17 @@ pub fn randomize() -> Element {
                 }
             }
         },
+        if !valid {
+            div {
+                class: "validation-errors",
+                "{json::report(&errors)}"
+            }
+        }
+        if let Some(seed_hash) = seed_hash_state() {
+            div {
+                class: "seed-hash",
+                "Seed hash: {seed_hash}"
+            }
+        }
     }
 }
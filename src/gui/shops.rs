@@ -1,7 +1,8 @@
 use dioxus::prelude::*;
 
+use crate::gui::item_pools::{from_text, invalid_class, to_text};
 use crate::gui::number_field;
-use crate::json::{Preset, ShopItems};
+use crate::json::{invalid, GuaranteedPlacement, Preset, PriceMode, ShopItems};
 
 use crate::gui::checkbox;
 
@@ -10,16 +11,37 @@ pub fn shops() -> Element {
     let mut preset_state = use_context::<Signal<Preset>>();
 
     let read_preset_state = preset_state();
+    let errors = read_preset_state.validate();
 
     let enabled = read_preset_state.randomizer.shops.enabled;
     let limit_enabled = read_preset_state.randomizer.shops.limit_shop_items_enabled;
     let selected = read_preset_state.randomizer.shops.items_only.clone();
+    let item_pools = read_preset_state.randomizer.item_pools.clone();
 
     let limit = read_preset_state.randomizer.shops.limit_shop_items;
 
+    let guaranteed_items = read_preset_state.randomizer.shops.guaranteed_items.clone();
+    let guaranteed_placement = read_preset_state.randomizer.shops.guaranteed_placement;
+    let guaranteed_shop_count = read_preset_state.randomizer.shops.guaranteed_shop_count as i64;
+    let region_shops: Vec<String> = read_preset_state
+        .randomizer
+        .shops
+        .region_shops
+        .iter()
+        .map(|x| x.to_string())
+        .collect();
+    let region_shops = region_shops.join(", ");
+
+    let progression = read_preset_state.randomizer.shops.progression;
+    let progression_min_price = read_preset_state.randomizer.shops.progression_min_price as i64;
+    let progression_max_price = read_preset_state.randomizer.shops.progression_max_price as i64;
+
     let sell_price = read_preset_state.randomizer.shops.sell_price;
     let min_sell_price = read_preset_state.randomizer.shops.min_sell_price;
     let max_sell_price = read_preset_state.randomizer.shops.max_sell_price;
+    let price_mode = read_preset_state.randomizer.shops.price_mode;
+    let price_variance = read_preset_state.randomizer.shops.price_variance as i64;
+    let buy_sell_ratio = read_preset_state.randomizer.shops.buy_sell_ratio as i64;
     let keep_tnt = read_preset_state.randomizer.shops.keep_tnt;
     let healing_ironmon = read_preset_state.randomizer.shops.healing_ironmon;
 
@@ -52,9 +74,10 @@ pub fn shops() -> Element {
                     },
                     select {
                         id: "shops.items_only",
+                        class: invalid_class(&errors, "randomizer.shops.itemsOnly"),
                         disabled: !enabled,
                         onchange: move |x: Event<FormData>| {
-                            preset_state.write().randomizer.shops.items_only = ShopItems::from(x.data.value().parse::<u8>().unwrap_or(0));
+                            preset_state.write().randomizer.shops.items_only = ShopItems::from_value(&x.data.value());
                         },
                         option {
                             value: "0",
@@ -71,6 +94,13 @@ pub fn shops() -> Element {
                             selected: selected == ShopItems::Ironmon,
                             "Ironmon"
                         },
+                        for pool in item_pools.iter() {
+                            option {
+                                value: "custom:{pool.name}",
+                                selected: selected == ShopItems::Custom(pool.name.clone()),
+                                "{pool.name}"
+                            },
+                        }
                     }
                 },
             },
@@ -117,6 +147,134 @@ pub fn shops() -> Element {
                     },
                 },
             },
+            div {
+                class: "left",
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        style: "width: 250px;",
+                        "Items placed before the random fill",
+                        br {},
+                        "Ids (decimal or 0x hex) and item names",
+                    },
+                    label {
+                        r#for: "shops.guaranteed_items",
+                        "Guaranteed"
+                    },
+                    input {
+                        id: "shops.guaranteed_items",
+                        r#type: "text",
+                        value: "{to_text(&guaranteed_items)}",
+                        disabled: !enabled,
+                        onchange: move |x: Event<FormData>| {
+                            preset_state.write().randomizer.shops.guaranteed_items = from_text(&x.data.value());
+                        }
+                    },
+                },
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        style: "width: 250px;",
+                        "Shops => in the given number of random shops",
+                        br {},
+                        "Regions => in the first shop of every region",
+                    },
+                    select {
+                        id: "shops.guaranteed_placement",
+                        disabled: !enabled,
+                        onchange: move |x: Event<FormData>| {
+                            preset_state.write().randomizer.shops.guaranteed_placement = GuaranteedPlacement::from(x.data.value().parse::<u8>().unwrap_or(0));
+                        },
+                        option {
+                            value: "0",
+                            selected: guaranteed_placement == GuaranteedPlacement::Shops,
+                            "Shops"
+                        },
+                        option {
+                            value: "1",
+                            selected: guaranteed_placement == GuaranteedPlacement::Regions,
+                            "Regions"
+                        },
+                    }
+                },
+                number_field::number_field {
+                    id: "shops.guaranteed_shop_count",
+                    invalid: invalid(&errors, "randomizer.shops.guaranteedShopCount"),
+                    label: "Count",
+                    disabled: !enabled || guaranteed_placement != GuaranteedPlacement::Shops,
+                    onchange: move |x: i64| {
+                        preset_state.write().randomizer.shops.guaranteed_shop_count = x as u8;
+                    },
+                    value: guaranteed_shop_count,
+                    min: 1,
+                    max: dmw3_consts::SHOPS_LEN as i64
+                },
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        "Index of the first shop of every region",
+                    },
+                    label {
+                        r#for: "shops.region_shops",
+                        "Region shops"
+                    },
+                    input {
+                        id: "shops.region_shops",
+                        r#type: "text",
+                        class: invalid_class(&errors, "randomizer.shops.regionShops"),
+                        value: "{region_shops}",
+                        disabled: !enabled || guaranteed_placement != GuaranteedPlacement::Regions,
+                        onchange: move |x: Event<FormData>| {
+                            preset_state.write().randomizer.shops.region_shops = x
+                                .data
+                                .value()
+                                .split(',')
+                                .filter_map(|shop| shop.trim().parse::<u8>().ok())
+                                .collect();
+                        }
+                    },
+                },
+            }
+            div {
+                class: "left",
+                checkbox::checkbox {
+                    id: "shops.progression",
+                    label: "Progression",
+                    disabled: !enabled,
+                    checked: progression,
+                    tooltip: "Limit shops to items up to a vanilla price that rises with each shop",
+                    onchange: move |x: bool| {
+                        preset_state.write().randomizer.shops.progression = x;
+                    },
+                },
+                number_field::number_field {
+                    id: "shops.progression_min_price",
+                    invalid: invalid(&errors, "randomizer.shops.progressionMinPrice"),
+                    label: "First",
+                    disabled: !enabled || !progression,
+                    onchange: move |x: i64| {
+                        preset_state.write().randomizer.shops.progression_min_price = x as u16;
+                    },
+                    value: progression_min_price,
+                    min: 0,
+                    max: progression_max_price
+                },
+                number_field::number_field {
+                    id: "shops.progression_max_price",
+                    invalid: invalid(&errors, "randomizer.shops.progressionMaxPrice"),
+                    label: "Last",
+                    disabled: !enabled || !progression,
+                    onchange: move |x: i64| {
+                        preset_state.write().randomizer.shops.progression_max_price = x as u16;
+                    },
+                    value: progression_max_price,
+                    min: progression_min_price,
+                    max: u16::MAX as i64
+                },
+            },
             div {
                 class: "left",
                 checkbox::checkbox {
@@ -139,12 +297,59 @@ pub fn shops() -> Element {
                     },
                 },
             },
+            div {
+                class: "left",
+                div {
+                    class: "tooltip",
+                    span {
+                        class: "tooltiptext",
+                        style: "width: 250px;",
+                        "Uniform => anywhere between min and max",
+                        br {},
+                        "Variance => vanilla price +- variance %",
+                    },
+                    label {
+                        r#for: "shops.price_mode",
+                        "Pricing"
+                    },
+                    select {
+                        id: "shops.price_mode",
+                        disabled: !enabled || !sell_price,
+                        onchange: move |x: Event<FormData>| {
+                            preset_state.write().randomizer.shops.price_mode = PriceMode::from(x.data.value().parse::<u8>().unwrap_or(0));
+                        },
+                        option {
+                            value: "0",
+                            selected: price_mode == PriceMode::Uniform,
+                            "Uniform"
+                        },
+                        option {
+                            value: "1",
+                            selected: price_mode == PriceMode::Variance,
+                            "Variance"
+                        },
+                    }
+                },
+                number_field::number_field {
+                    id: "shops.price_variance",
+                    invalid: invalid(&errors, "randomizer.shops.priceVariance"),
+                    label: "Variance %",
+                    disabled: !enabled || !sell_price || price_mode != PriceMode::Variance,
+                    onchange: move |x: i64| {
+                        preset_state.write().randomizer.shops.price_variance = x as u16;
+                    },
+                    value: price_variance,
+                    min: 0,
+                    max: 100
+                },
+            }
             div {
                 class: "left",
                 number_field::number_field {
                     id: "shops.min_sell_price",
+                    invalid: invalid(&errors, "randomizer.shops.minSellPrice"),
                     label: "Min",
-                    disabled: !enabled || !sell_price,
+                    disabled: !enabled || !sell_price || price_mode != PriceMode::Uniform,
                     onchange: move |x: i64| {
                         preset_state.write().randomizer.shops.min_sell_price = x;
                     },
@@ -154,8 +359,9 @@ pub fn shops() -> Element {
                 },
                 number_field::number_field {
                     id: "shops.max_sell_price",
+                    invalid: invalid(&errors, "randomizer.shops.maxSellPrice"),
                     label: "Max",
-                    disabled: !enabled || !sell_price,
+                    disabled: !enabled || !sell_price || price_mode != PriceMode::Uniform,
                     onchange: move |x: i64| {
                         preset_state.write().randomizer.shops.max_sell_price = x;
                     },
@@ -164,6 +370,22 @@ pub fn shops() -> Element {
                     max: dmw3_consts::MAX_SELL_PRICE
                 },
             }
+            div {
+                class: "left",
+                number_field::number_field {
+                    id: "shops.buy_sell_ratio",
+                    invalid: invalid(&errors, "randomizer.shops.buySellRatio"),
+                    label: "Buy / sell %",
+                    tooltip: "Buy price as a percentage of the sell price",
+                    disabled: !enabled,
+                    onchange: move |x: i64| {
+                        preset_state.write().randomizer.shops.buy_sell_ratio = x as u16;
+                    },
+                    value: buy_sell_ratio,
+                    min: 1,
+                    max: 1000
+                },
+            }
             div {
                 class: "left",
                 checkbox::checkbox {
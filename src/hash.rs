@@ -0,0 +1,73 @@
+use std::fmt;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{json::Preset, objects::Objects};
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+// how many names are shown to the player
+const NAME_COUNT: usize = 4;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SeedHash {
+    pub value: u64,
+    pub names: Vec<String>,
+}
+
+impl fmt::Display for SeedHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016X} ({})", self.value, self.names.join(" / "))
+    }
+}
+
+// FNV-1a, std hashers aren't stable between rust versions
+fn fnv1a(bytes: &[u8], mut hash: u64) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    hash
+}
+
+pub fn seed_hash(preset: &Preset, objects: &Objects) -> anyhow::Result<SeedHash> {
+    let mut value = FNV_OFFSET;
+
+    value = fnv1a(serde_json::to_string(preset)?.as_bytes(), value);
+    value = fnv1a(&preset.randomizer.seed.to_le_bytes(), value);
+    value = fnv1a(objects.executable.as_str().as_bytes(), value);
+    value = fnv1a(env!("CARGO_PKG_VERSION").as_bytes(), value);
+
+    let lang = objects
+        .executable
+        .languages()
+        .first()
+        .context("executable with no languages")?;
+
+    let item_names = &objects
+        .items
+        .files
+        .get(lang)
+        .context("failed to get by lang")?
+        .file
+        .files;
+
+    let pool: Vec<usize> = (1..objects.item_shop_data.original.len())
+        .filter(|i| objects.item_shop_data.original[*i].sell_price > 0 && *i < item_names.len())
+        .collect();
+
+    anyhow::ensure!(!pool.is_empty(), "no sellable item names for the seed hash");
+
+    let names = (0..NAME_COUNT)
+        .map(|i| {
+            let chunk = (value >> (i * 16)) & 0xffff;
+
+            lang.decode(&item_names[pool[chunk as usize % pool.len()]])
+        })
+        .collect();
+
+    Ok(SeedHash { value, names })
+}
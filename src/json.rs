@@ -1,10 +1,19 @@
+use anyhow::Context;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::fs;
 
+mod migrate;
+mod validate;
+
+pub use migrate::{migrate, PRESET_VERSION};
+pub use validate::{invalid, report, ValidationError};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Preset {
+    #[serde(default = "default_version")]
+    pub version: u32,
     #[serde(default = "default_randomizer")]
     pub randomizer: Randomizer,
     #[serde(default = "default_fixes")]
@@ -36,6 +45,14 @@ pub struct Randomizer {
     pub models: Models,
     #[serde(default = "default_auction")]
     pub auctions: Auction,
+    #[serde(default = "default_drops")]
+    pub drops: Drops,
+    #[serde(default = "default_moves")]
+    pub moves: Moves,
+    #[serde(default = "default_charisma")]
+    pub charisma: Charisma,
+    #[serde(default = "Vec::new")]
+    pub item_pools: Vec<ItemPool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -71,14 +88,40 @@ pub struct Encounters {
     pub enabled: bool,
     #[serde(default = "default_bool_true")]
     pub cardmon: bool,
-    #[serde(default = "default_bool_true")]
-    pub bosses: bool,
+    #[serde(default = "BossStrategy::default")]
+    pub bosses: BossStrategy,
     #[serde(default = "default_bool_true")]
     pub keep_zanbamon: bool,
     #[serde(default = "default_bool_true")]
     pub keep_galacticmon: bool,
     #[serde(default = "TNTStrategy::default")]
     pub strategy: TNTStrategy,
+    #[serde(default = "EncounterTier::default")]
+    pub tier: EncounterTier,
+    #[serde(default = "default_level_band")]
+    pub level_band: u16,
+    #[serde(default = "default_bool_false")]
+    pub teams: bool,
+    #[serde(default = "default_min_team_size")]
+    pub min_team_size: u8,
+    #[serde(default = "default_max_team_size")]
+    pub max_team_size: u8,
+    #[serde(default = "TeamTheme::default")]
+    pub team_theme: TeamTheme,
+    #[serde(default = "default_bool_false")]
+    pub enemy_techs: bool,
+    #[serde(default = "TechPool::default")]
+    pub enemy_tech_pool: TechPool,
+    #[serde(default = "default_bool_false")]
+    pub resistances: bool,
+    #[serde(default = "default_min_resistance")]
+    pub min_resistance: i16,
+    #[serde(default = "default_max_resistance")]
+    pub max_resistance: i16,
+    #[serde(default = "default_bool_true")]
+    pub keep_total_resistance: bool,
+    #[serde(default = "default_bool_false")]
+    pub single_weakness: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -125,6 +168,12 @@ pub struct Parties {
     pub enabled: bool,
     #[serde(default = "default_bool_true")]
     pub parties: bool,
+    #[serde(default = "PartyMode::default")]
+    pub party_mode: PartyMode,
+    #[serde(default = "default_starting_rookie")]
+    pub starting_rookie: u8,
+    #[serde(default = "default_bool_false")]
+    pub random_previews: bool,
     #[serde(default = "default_bool_true")]
     pub stat_distribution: bool,
     #[serde(default = "default_bool_true")]
@@ -137,14 +186,50 @@ pub struct Parties {
     pub total_starting_res: u16,
     #[serde(default = "default_min_starting_res")]
     pub min_starting_res: u16,
+    #[serde(default = "default_bool_false")]
+    pub dv_stat_distribution: bool,
+    #[serde(default = "default_dv_total_stats")]
+    pub dv_total_stats: u16,
+    #[serde(default = "default_dv_min_stat")]
+    pub dv_min_stat: u16,
+    #[serde(default = "default_bool_false")]
+    pub dv_res_distribution: bool,
+    #[serde(default = "default_dv_total_res")]
+    pub dv_total_res: u16,
+    #[serde(default = "default_dv_min_res")]
+    pub dv_min_res: u16,
+    #[serde(default = "default_dv_stage_scaling")]
+    pub dv_stage_scaling: u16,
+    #[serde(default = "AffinityMode::default")]
+    pub affinities: AffinityMode,
+    #[serde(default = "default_min_affinity")]
+    pub min_affinity: u8,
+    #[serde(default = "default_max_affinity")]
+    pub max_affinity: u8,
     #[serde(default = "default_bool_true")]
     pub learned_tech: bool,
+    #[serde(default = "default_bool_false")]
+    pub learned_tech_tier: bool,
+    #[serde(default = "default_bool_false")]
+    pub learned_tech_element: bool,
+    #[serde(default = "default_bool_false")]
+    pub learned_tech_attack: bool,
     #[serde(default = "default_bool_true")]
     pub signatures: bool,
     #[serde(default = "default_bool_true")]
     pub digivolutions: bool,
     #[serde(default = "default_bool_true")]
     pub keep_stages: bool,
+    #[serde(default = "default_bool_false")]
+    pub shuffle_trees: bool,
+    #[serde(default = "DvRequirements::default")]
+    pub requirements: DvRequirements,
+    #[serde(default = "default_min_requirement_level")]
+    pub min_requirement_level: u16,
+    #[serde(default = "default_max_requirement_level")]
+    pub max_requirement_level: u16,
+    #[serde(default = "default_easy_requirement_percent")]
+    pub easy_requirement_percent: u16,
     #[serde(default = "default_bool_true")]
     pub exp_modifier: bool,
     #[serde(default = "default_min_exp_modifier")]
@@ -192,12 +277,51 @@ pub struct Shops {
     pub min_sell_price: i64,
     #[serde(default = "default_max_sell_price")]
     pub max_sell_price: i64,
+    #[serde(default = "Vec::new")]
+    pub guaranteed_items: Vec<ItemRef>,
+    #[serde(default = "GuaranteedPlacement::default")]
+    pub guaranteed_placement: GuaranteedPlacement,
+    #[serde(default = "default_guaranteed_shop_count")]
+    pub guaranteed_shop_count: u8,
+    // index of the first shop of every region
+    #[serde(default = "default_region_shops")]
+    pub region_shops: Vec<u8>,
+    #[serde(default = "default_bool_false")]
+    pub progression: bool,
+    #[serde(default = "default_progression_min_price")]
+    pub progression_min_price: u16,
+    #[serde(default = "default_progression_max_price")]
+    pub progression_max_price: u16,
+    #[serde(default = "PriceMode::default")]
+    pub price_mode: PriceMode,
+    #[serde(default = "default_price_variance")]
+    pub price_variance: u16,
+    #[serde(default = "default_buy_sell_ratio")]
+    pub buy_sell_ratio: u16,
+    #[serde(default = "Vec::new")]
+    pub price_rules: Vec<PriceRule>,
     #[serde(default = "default_bool_true")]
     pub keep_tnt: bool,
     #[serde(default = "default_bool_false")]
     pub healing_ironmon: bool,
 }
 
+// prices for the items of a pool, the first matching rule wins and items
+// without one use the shop wide settings
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceRule {
+    pub pool: ShopItems,
+    #[serde(default = "PriceMode::default")]
+    pub price_mode: PriceMode,
+    #[serde(default = "default_min_sell_price")]
+    pub min_sell_price: i64,
+    #[serde(default = "default_max_sell_price")]
+    pub max_sell_price: i64,
+    #[serde(default = "default_price_variance")]
+    pub price_variance: u16,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Auction {
@@ -215,6 +339,53 @@ pub struct Auction {
     pub auction_values_max: u32,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Drops {
+    #[serde(default = "default_bool_false")]
+    pub enabled: bool,
+    #[serde(default = "ShopItems::default")]
+    pub items_pool: ShopItems,
+    #[serde(default = "default_min_drop_rate")]
+    pub min_drop_rate: u16,
+    #[serde(default = "default_max_drop_rate")]
+    pub max_drop_rate: u16,
+    #[serde(default = "default_bool_true")]
+    pub keep_boss_drops: bool,
+    #[serde(default = "default_bool_false")]
+    pub no_drops: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Moves {
+    #[serde(default = "default_bool_false")]
+    pub enabled: bool,
+    #[serde(default = "default_power_variance")]
+    pub power_variance: u8,
+    #[serde(default = "default_bool_true")]
+    pub mp_from_power: bool,
+    #[serde(default = "default_bool_false")]
+    pub elements: bool,
+    #[serde(default = "default_bool_false")]
+    pub multi_hits: bool,
+    #[serde(default = "default_min_hits")]
+    pub min_hits: u8,
+    #[serde(default = "default_max_hits")]
+    pub max_hits: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Charisma {
+    #[serde(default = "default_bool_false")]
+    pub enabled: bool,
+    #[serde(default = "CharismaReqs::default")]
+    pub requirements: CharismaReqs,
+    #[serde(default = "default_charisma_multiplier")]
+    pub multiplier: u16,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CardGame {
@@ -266,6 +437,10 @@ fn default_shop_limit() -> u8 {
     8
 }
 
+fn default_version() -> u32 {
+    PRESET_VERSION
+}
+
 fn default_randomizer() -> Randomizer {
     serde_json::from_str("{}").unwrap()
 }
@@ -294,6 +469,18 @@ fn default_auction() -> Auction {
     serde_json::from_str("{}").unwrap()
 }
 
+fn default_drops() -> Drops {
+    serde_json::from_str("{}").unwrap()
+}
+
+fn default_moves() -> Moves {
+    serde_json::from_str("{}").unwrap()
+}
+
+fn default_charisma() -> Charisma {
+    serde_json::from_str("{}").unwrap()
+}
+
 fn default_card_game() -> CardGame {
     serde_json::from_str("{}").unwrap()
 }
@@ -358,6 +545,30 @@ fn default_max_sell_price() -> i64 {
     6000
 }
 
+fn default_guaranteed_shop_count() -> u8 {
+    3
+}
+
+fn default_region_shops() -> Vec<u8> {
+    crate::rand::REGION_SHOPS.to_vec()
+}
+
+fn default_progression_min_price() -> u16 {
+    1000
+}
+
+fn default_progression_max_price() -> u16 {
+    20000
+}
+
+fn default_price_variance() -> u16 {
+    25
+}
+
+fn default_buy_sell_ratio() -> u16 {
+    200
+}
+
 fn default_min_card_buy_price() -> i64 {
     500
 }
@@ -422,6 +633,54 @@ fn default_max_starting_mp() -> u8 {
     200
 }
 
+fn default_charisma_multiplier() -> u16 {
+    100
+}
+
+fn default_dv_total_stats() -> u16 {
+    100
+}
+
+fn default_dv_min_stat() -> u16 {
+    5
+}
+
+fn default_dv_total_res() -> u16 {
+    140
+}
+
+fn default_dv_min_res() -> u16 {
+    5
+}
+
+fn default_dv_stage_scaling() -> u16 {
+    50
+}
+
+fn default_min_affinity() -> u8 {
+    dmw3_consts::MIN_STAT_AFFINITY
+}
+
+fn default_max_affinity() -> u8 {
+    dmw3_consts::MAX_STAT_AFFINITY
+}
+
+fn default_starting_rookie() -> u8 {
+    0
+}
+
+fn default_min_requirement_level() -> u16 {
+    1
+}
+
+fn default_max_requirement_level() -> u16 {
+    20
+}
+
+fn default_easy_requirement_percent() -> u16 {
+    50
+}
+
 fn default_min_auction_values() -> u32 {
     800
 }
@@ -430,6 +689,46 @@ fn default_max_auction_values() -> u32 {
     10000
 }
 
+fn default_level_band() -> u16 {
+    5
+}
+
+fn default_min_team_size() -> u8 {
+    1
+}
+
+fn default_max_team_size() -> u8 {
+    3
+}
+
+fn default_min_resistance() -> i16 {
+    30
+}
+
+fn default_max_resistance() -> i16 {
+    300
+}
+
+fn default_power_variance() -> u8 {
+    20
+}
+
+fn default_min_hits() -> u8 {
+    2
+}
+
+fn default_max_hits() -> u8 {
+    5
+}
+
+fn default_min_drop_rate() -> u16 {
+    5
+}
+
+fn default_max_drop_rate() -> u16 {
+    50
+}
+
 fn default_music_pool_overworld() -> MusicPool {
     MusicPool::Overworld
 }
@@ -460,6 +759,170 @@ impl From<u8> for TNTStrategy {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum BossStrategy {
+    Keep,
+    #[default]
+    Mixed,
+    Separate,
+}
+
+impl From<u8> for BossStrategy {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => BossStrategy::Keep,
+            1 => BossStrategy::Mixed,
+            _ => BossStrategy::Separate,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum CharismaReqs {
+    Keep,
+    #[default]
+    Scale,
+    Random,
+}
+
+impl From<u8> for CharismaReqs {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => CharismaReqs::Keep,
+            1 => CharismaReqs::Scale,
+            _ => CharismaReqs::Random,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum AffinityMode {
+    #[default]
+    Linked,
+    Random,
+    Vanilla,
+}
+
+impl From<u8> for AffinityMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => AffinityMode::Linked,
+            1 => AffinityMode::Random,
+            _ => AffinityMode::Vanilla,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum PartyMode {
+    #[default]
+    Random,
+    Fixed,
+    // every pack is led by the same random rookie, the other slots are still shuffled
+    RandomLeader,
+    // every slot holds the same random rookie
+    Single,
+}
+
+impl From<u8> for PartyMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => PartyMode::Random,
+            1 => PartyMode::Fixed,
+            2 => PartyMode::RandomLeader,
+            _ => PartyMode::Single,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum DvRequirements {
+    #[default]
+    Keep,
+    Random,
+    Easy,
+}
+
+impl From<u8> for DvRequirements {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => DvRequirements::Keep,
+            1 => DvRequirements::Random,
+            _ => DvRequirements::Easy,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum EncounterTier {
+    #[default]
+    Global,
+    Stage,
+    Level,
+}
+
+impl From<u8> for EncounterTier {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => EncounterTier::Global,
+            1 => EncounterTier::Stage,
+            _ => EncounterTier::Level,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum TeamTheme {
+    #[default]
+    Random,
+    Element,
+    Stage,
+}
+
+impl From<u8> for TeamTheme {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => TeamTheme::Random,
+            1 => TeamTheme::Element,
+            _ => TeamTheme::Stage,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum TechPool {
+    Element,
+    HitEffect,
+    #[default]
+    Any,
+}
+
+impl From<u8> for TechPool {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => TechPool::Element,
+            1 => TechPool::HitEffect,
+            _ => TechPool::Any,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 #[derive(Default)]
@@ -488,6 +951,8 @@ pub enum ShopItems {
     #[default]
     Sellable,
     Ironmon,
+    // name of a pool in randomizer.itemPools
+    Custom(String),
 }
 
 impl From<u8> for ShopItems {
@@ -500,6 +965,88 @@ impl From<u8> for ShopItems {
     }
 }
 
+impl ShopItems {
+    // select values, built-in pools are numbered and custom pools are "custom:<name>"
+    pub fn from_value(value: &str) -> ShopItems {
+        match value.strip_prefix("custom:") {
+            Some(name) => ShopItems::Custom(String::from(name)),
+            None => ShopItems::from(value.parse::<u8>().unwrap_or(0)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum GuaranteedPlacement {
+    // guaranteed shop count random shops
+    #[default]
+    Shops,
+    // the first shop of every region
+    Regions,
+}
+
+impl From<u8> for GuaranteedPlacement {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => GuaranteedPlacement::Shops,
+            _ => GuaranteedPlacement::Regions,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum PriceMode {
+    // anywhere between min and max sell price
+    #[default]
+    Uniform,
+    // vanilla price +- variance percent
+    Variance,
+}
+
+impl From<u8> for PriceMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => PriceMode::Uniform,
+            _ => PriceMode::Variance,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemCategory {
+    Buyable,
+    Sellable,
+    // stocked by a shop in the vanilla game
+    Stocked,
+    // dropped by an enemy in the vanilla game
+    Dropped,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum ItemRef {
+    Id(u16),
+    // matched against item names of every language, case insensitive
+    Name(String),
+}
+
+// items of any of the categories plus include, minus exclude
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemPool {
+    pub name: String,
+    #[serde(default = "Vec::new")]
+    pub categories: Vec<ItemCategory>,
+    #[serde(default = "Vec::new")]
+    pub include: Vec<ItemRef>,
+    #[serde(default = "Vec::new")]
+    pub exclude: Vec<ItemRef>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum MusicPool {
@@ -518,15 +1065,27 @@ impl From<u8> for MusicPool {
     }
 }
 
-pub fn load_preset(path: &Option<std::path::PathBuf>) -> Box<Preset> {
+pub fn load_preset(path: &Option<std::path::PathBuf>) -> anyhow::Result<Box<Preset>> {
     match path {
         Some(path) => {
-            let json_str = fs::read_to_string(path).unwrap();
+            let json_str = fs::read_to_string(path)
+                .with_context(|| format!("failed to read preset {}", path.display()))?;
 
-            let preset: Preset = serde_json::from_str(json_str.as_str()).unwrap();
+            let migrated = migrate(json_str.as_str())
+                .with_context(|| format!("failed to parse preset {}", path.display()))?;
 
-            Box::new(preset)
+            for warning in migrated.warnings {
+                println!("{}: {warning}", path.display());
+            }
+
+            Ok(Box::new(migrated.preset))
         }
-        None => Box::new(serde_json::from_str("{}").unwrap()),
+        None => Ok(Box::new(serde_json::from_str("{}")?)),
     }
 }
+
+pub fn save_preset(path: &std::path::PathBuf, preset: &Preset) -> anyhow::Result<()> {
+    let json_str = serde_json::to_string_pretty(preset)?;
+
+    fs::write(path, json_str).with_context(|| format!("failed to write preset {}", path.display()))
+}
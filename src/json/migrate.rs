@@ -0,0 +1,228 @@
+use anyhow::Context;
+use serde_json::Value;
+
+use crate::json::Preset;
+
+pub const PRESET_VERSION: u32 = 2;
+
+// MIGRATIONS[i] upgrades a version i preset to version i + 1
+const MIGRATIONS: [fn(&mut Value); PRESET_VERSION as usize] = [v0_camel_case, v1_boss_strategy];
+
+pub struct Migrated {
+    pub preset: Preset,
+    pub warnings: Vec<String>,
+}
+
+fn to_camel_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len());
+    let mut upper = false;
+
+    for c in key.chars() {
+        match c {
+            '_' => upper = true,
+            _ if upper => {
+                result.extend(c.to_uppercase());
+                upper = false;
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+fn camel_case_keys(value: &mut Value) {
+    let Value::Object(map) = value else {
+        return;
+    };
+
+    let (camel, snake): (Vec<_>, Vec<_>) = std::mem::take(map)
+        .into_iter()
+        .partition(|(key, _)| !key.contains('_'));
+
+    // keys that are already camelCase take priority over their snake_case duplicates
+    for (key, value) in camel {
+        map.insert(key, value);
+    }
+
+    for (key, value) in snake {
+        map.entry(to_camel_case(&key)).or_insert(value);
+    }
+
+    for value in map.values_mut() {
+        camel_case_keys(value);
+    }
+}
+
+// unversioned presets (like example.json) used snake_case keys everywhere,
+// which were silently dropped for the camelCase sections
+fn v0_camel_case(value: &mut Value) {
+    let Value::Object(map) = value else {
+        return;
+    };
+
+    if let Some(party_exp_bits) = map.remove("party_exp_bits") {
+        map.entry("partyExpBits").or_insert(party_exp_bits);
+    }
+
+    for key in ["randomizer", "partyExpBits"] {
+        if let Some(section) = map.get_mut(key) {
+            camel_case_keys(section);
+        }
+    }
+}
+
+// encounters.bosses went from a bool to keep / mixed / separate
+fn v1_boss_strategy(value: &mut Value) {
+    let Some(bosses) = value.pointer_mut("/randomizer/encounters/bosses") else {
+        return;
+    };
+
+    if let Value::Bool(shuffle) = *bosses {
+        *bosses = Value::from(match shuffle {
+            true => "mixed",
+            false => "keep",
+        });
+    }
+}
+
+fn unknown_keys(value: &Value, known: &Value, path: &str, result: &mut Vec<String>) {
+    let (Value::Object(map), Value::Object(known_map)) = (value, known) else {
+        return;
+    };
+
+    for (key, value) in map {
+        let key_path = match path {
+            "" => key.clone(),
+            _ => format!("{path}.{key}"),
+        };
+
+        match known_map.get(key) {
+            Some(known) => unknown_keys(value, known, &key_path, result),
+            None => result.push(key_path),
+        }
+    }
+}
+
+pub fn migrate(json_str: &str) -> anyhow::Result<Migrated> {
+    let mut value: Value = serde_json::from_str(json_str)?;
+    let mut warnings = Vec::new();
+
+    if !value.is_object() {
+        anyhow::bail!("preset is not a json object");
+    }
+
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+
+    if version > PRESET_VERSION {
+        warnings.push(format!(
+            "preset version {version} is newer than supported version {PRESET_VERSION}"
+        ));
+    }
+
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        migration(&mut value);
+    }
+
+    if let Value::Object(map) = &mut value {
+        map.insert(String::from("version"), Value::from(PRESET_VERSION));
+    }
+
+    let known = serde_json::to_value(serde_json::from_str::<Preset>("{}")?)?;
+
+    let mut unknown = Vec::new();
+    unknown_keys(&value, &known, "", &mut unknown);
+
+    for key in unknown {
+        warnings.push(format!("ignoring unknown key {key}"));
+    }
+
+    let preset = serde_json::from_value(value).context("failed to parse migrated preset")?;
+
+    Ok(Migrated { preset, warnings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snake_case_to_camel_case() {
+        assert_eq!(to_camel_case("min_sell_price"), "minSellPrice");
+        assert_eq!(to_camel_case("enabled"), "enabled");
+    }
+
+    #[test]
+    fn v0_preset() {
+        let migrated = migrate(
+            r#"{
+                "randomizer": {
+                    "shops": { "limit_shop_items": 5, "sell_price": false },
+                    "encounters": { "bosses": true }
+                },
+                "party_exp_bits": { "exp_modifier": 3.0 }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(migrated.preset.randomizer.shops.limit_shop_items, 5);
+        assert!(!migrated.preset.randomizer.shops.sell_price);
+        assert_eq!(migrated.preset.party_exp_bits.exp_modifier, 3.0);
+        assert_eq!(
+            serde_json::to_value(migrated.preset.randomizer.encounters.bosses).unwrap(),
+            Value::from("mixed")
+        );
+        assert!(migrated.warnings.is_empty());
+    }
+
+    #[test]
+    fn camel_case_wins_over_snake_case() {
+        let migrated = migrate(
+            r#"{ "randomizer": { "shops": { "limitShopItems": 4, "limit_shop_items": 6 } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(migrated.preset.randomizer.shops.limit_shop_items, 4);
+    }
+
+    #[test]
+    fn v1_boss_strategy() {
+        let migrated =
+            migrate(r#"{ "version": 1, "randomizer": { "encounters": { "bosses": false } } }"#)
+                .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(migrated.preset.randomizer.encounters.bosses).unwrap(),
+            Value::from("keep")
+        );
+    }
+
+    #[test]
+    fn current_version_is_kept() {
+        let migrated = migrate(&format!(
+            r#"{{ "version": {PRESET_VERSION}, "randomizer": {{ "shops": {{ "limit_shop_items": 5 }} }} }}"#
+        ))
+        .unwrap();
+
+        assert_ne!(migrated.preset.randomizer.shops.limit_shop_items, 5);
+        assert_eq!(
+            migrated.warnings,
+            vec![String::from(
+                "ignoring unknown key randomizer.shops.limit_shop_items"
+            )]
+        );
+    }
+
+    #[test]
+    fn newer_version_warns() {
+        let migrated = migrate(r#"{ "version": 99 }"#).unwrap();
+
+        assert_eq!(migrated.warnings.len(), 1);
+        assert!(migrated.warnings[0].starts_with("preset version 99 is newer"));
+    }
+
+    #[test]
+    fn not_an_object() {
+        assert!(migrate("[]").is_err());
+    }
+}
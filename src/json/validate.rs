@@ -0,0 +1,777 @@
+use std::fmt;
+
+use crate::json::{ItemPool, ItemRef, Preset, ShopItems};
+use crate::rand::{MAX_HITS, MAX_RESISTANCE, MAX_TEAM_SIZE};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    MinAboveMax {
+        min_path: String,
+        max_path: String,
+        min: i64,
+        max: i64,
+    },
+    OutOfRange {
+        path: String,
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+    TotalBelowMinimum {
+        total_path: String,
+        min_path: String,
+        total: i64,
+        min: i64,
+        count: i64,
+    },
+    DuplicateName {
+        path: String,
+        name: String,
+    },
+    UnknownPool {
+        path: String,
+        name: String,
+    },
+    InvalidItem {
+        path: String,
+        item: String,
+    },
+}
+
+impl ValidationError {
+    pub fn paths(&self) -> Vec<&str> {
+        match self {
+            ValidationError::MinAboveMax {
+                min_path, max_path, ..
+            } => vec![min_path, max_path],
+            ValidationError::OutOfRange { path, .. } => vec![path],
+            ValidationError::TotalBelowMinimum {
+                total_path,
+                min_path,
+                ..
+            } => vec![total_path, min_path],
+            ValidationError::DuplicateName { path, .. }
+            | ValidationError::UnknownPool { path, .. }
+            | ValidationError::InvalidItem { path, .. } => vec![path],
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::MinAboveMax {
+                min_path,
+                max_path,
+                min,
+                max,
+            } => write!(f, "{min_path}: {min} is greater than {max_path} ({max})"),
+            ValidationError::OutOfRange {
+                path,
+                value,
+                min,
+                max,
+            } => write!(f, "{path}: {value} is outside of [{min}, {max}]"),
+            ValidationError::TotalBelowMinimum {
+                total_path,
+                min_path,
+                total,
+                min,
+                count,
+            } => write!(
+                f,
+                "{total_path}: {total} is less than {count} * {min_path} ({})",
+                count * min
+            ),
+            ValidationError::DuplicateName { path, name } => {
+                write!(f, "{path}: {name} is used by more than one pool")
+            }
+            ValidationError::UnknownPool { path, name } => {
+                write!(f, "{path}: there is no item pool named {name}")
+            }
+            ValidationError::InvalidItem { path, item } => {
+                write!(f, "{path}: {item} is not an item id or name")
+            }
+        }
+    }
+}
+
+pub fn invalid(errors: &[ValidationError], path: &str) -> bool {
+    errors.iter().any(|x| x.paths().contains(&path))
+}
+
+pub fn report(errors: &[ValidationError]) -> String {
+    let lines: Vec<String> = errors.iter().map(|x| format!("  {x}")).collect();
+
+    format!("invalid preset:\n{}", lines.join("\n"))
+}
+
+fn min_max(errors: &mut Vec<ValidationError>, min_path: &str, min: i64, max_path: &str, max: i64) {
+    if min > max {
+        errors.push(ValidationError::MinAboveMax {
+            min_path: String::from(min_path),
+            max_path: String::from(max_path),
+            min,
+            max,
+        });
+    }
+}
+
+fn in_range(errors: &mut Vec<ValidationError>, path: &str, value: i64, min: i64, max: i64) {
+    if value < min || value > max {
+        errors.push(ValidationError::OutOfRange {
+            path: String::from(path),
+            value,
+            min,
+            max,
+        });
+    }
+}
+
+fn total(
+    errors: &mut Vec<ValidationError>,
+    total_path: &str,
+    total: i64,
+    min_path: &str,
+    min: i64,
+    count: i64,
+) {
+    if total < min * count {
+        errors.push(ValidationError::TotalBelowMinimum {
+            total_path: String::from(total_path),
+            min_path: String::from(min_path),
+            total,
+            min,
+            count,
+        });
+    }
+}
+
+fn pool_ref(errors: &mut Vec<ValidationError>, path: &str, pool: &ShopItems, pools: &[ItemPool]) {
+    if let ShopItems::Custom(name) = pool {
+        if !pools.iter().any(|x| x.name == *name) {
+            errors.push(ValidationError::UnknownPool {
+                path: String::from(path),
+                name: name.clone(),
+            });
+        }
+    }
+}
+
+// names are only resolved against the rom when randomizing, this catches
+// blank entries and numbers that aren't a valid id
+fn item_refs(errors: &mut Vec<ValidationError>, path: &str, refs: &[ItemRef]) {
+    for item in refs {
+        let ItemRef::Name(name) = item else {
+            continue;
+        };
+
+        let name = name.trim();
+
+        if name.is_empty() || name.starts_with("0x") || name.chars().all(|x| x.is_ascii_digit()) {
+            errors.push(ValidationError::InvalidItem {
+                path: String::from(path),
+                item: String::from(name),
+            });
+        }
+    }
+}
+
+impl Preset {
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        let encounters = &self.randomizer.encounters;
+
+        in_range(
+            &mut errors,
+            "randomizer.encounters.levelBand",
+            encounters.level_band as i64,
+            1,
+            99,
+        );
+
+        in_range(
+            &mut errors,
+            "randomizer.encounters.minTeamSize",
+            encounters.min_team_size as i64,
+            1,
+            MAX_TEAM_SIZE as i64,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.encounters.maxTeamSize",
+            encounters.max_team_size as i64,
+            1,
+            MAX_TEAM_SIZE as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.encounters.minTeamSize",
+            encounters.min_team_size as i64,
+            "randomizer.encounters.maxTeamSize",
+            encounters.max_team_size as i64,
+        );
+
+        let shops = &self.randomizer.shops;
+
+        in_range(
+            &mut errors,
+            "randomizer.shops.limitShopItems",
+            shops.limit_shop_items as i64,
+            dmw3_consts::MIN_SHOP_ITEMS as i64,
+            dmw3_consts::MAX_SHOP_ITEMS as i64,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.shops.minSellPrice",
+            shops.min_sell_price,
+            dmw3_consts::MIN_SELL_PRICE,
+            dmw3_consts::MAX_SELL_PRICE,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.shops.maxSellPrice",
+            shops.max_sell_price,
+            dmw3_consts::MIN_SELL_PRICE,
+            dmw3_consts::MAX_SELL_PRICE,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.shops.minSellPrice",
+            shops.min_sell_price,
+            "randomizer.shops.maxSellPrice",
+            shops.max_sell_price,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.shops.guaranteedShopCount",
+            shops.guaranteed_shop_count as i64,
+            1,
+            dmw3_consts::SHOPS_LEN as i64,
+        );
+
+        for shop in &shops.region_shops {
+            in_range(
+                &mut errors,
+                "randomizer.shops.regionShops",
+                *shop as i64,
+                0,
+                dmw3_consts::SHOPS_LEN as i64 - 1,
+            );
+        }
+        min_max(
+            &mut errors,
+            "randomizer.shops.progressionMinPrice",
+            shops.progression_min_price as i64,
+            "randomizer.shops.progressionMaxPrice",
+            shops.progression_max_price as i64,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.shops.priceVariance",
+            shops.price_variance as i64,
+            0,
+            100,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.shops.buySellRatio",
+            shops.buy_sell_ratio as i64,
+            1,
+            1000,
+        );
+
+        for (i, rule) in shops.price_rules.iter().enumerate() {
+            let min_path = format!("randomizer.shops.priceRules.{i}.minSellPrice");
+            let max_path = format!("randomizer.shops.priceRules.{i}.maxSellPrice");
+
+            in_range(
+                &mut errors,
+                &min_path,
+                rule.min_sell_price,
+                dmw3_consts::MIN_SELL_PRICE,
+                dmw3_consts::MAX_SELL_PRICE,
+            );
+            in_range(
+                &mut errors,
+                &max_path,
+                rule.max_sell_price,
+                dmw3_consts::MIN_SELL_PRICE,
+                dmw3_consts::MAX_SELL_PRICE,
+            );
+            min_max(
+                &mut errors,
+                &min_path,
+                rule.min_sell_price,
+                &max_path,
+                rule.max_sell_price,
+            );
+            in_range(
+                &mut errors,
+                &format!("randomizer.shops.priceRules.{i}.priceVariance"),
+                rule.price_variance as i64,
+                0,
+                100,
+            );
+        }
+
+        let auctions = &self.randomizer.auctions;
+
+        min_max(
+            &mut errors,
+            "randomizer.auctions.auctionValuesMin",
+            auctions.auction_values_min as i64,
+            "randomizer.auctions.auctionValuesMax",
+            auctions.auction_values_max as i64,
+        );
+
+        in_range(
+            &mut errors,
+            "randomizer.encounters.minResistance",
+            encounters.min_resistance as i64,
+            0,
+            MAX_RESISTANCE as i64,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.encounters.maxResistance",
+            encounters.max_resistance as i64,
+            0,
+            MAX_RESISTANCE as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.encounters.minResistance",
+            encounters.min_resistance as i64,
+            "randomizer.encounters.maxResistance",
+            encounters.max_resistance as i64,
+        );
+
+        let drops = &self.randomizer.drops;
+
+        in_range(
+            &mut errors,
+            "randomizer.drops.minDropRate",
+            drops.min_drop_rate as i64,
+            0,
+            100,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.drops.maxDropRate",
+            drops.max_drop_rate as i64,
+            0,
+            100,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.drops.minDropRate",
+            drops.min_drop_rate as i64,
+            "randomizer.drops.maxDropRate",
+            drops.max_drop_rate as i64,
+        );
+
+        let moves = &self.randomizer.moves;
+
+        in_range(
+            &mut errors,
+            "randomizer.moves.powerVariance",
+            moves.power_variance as i64,
+            0,
+            100,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.moves.minHits",
+            moves.min_hits as i64,
+            2,
+            MAX_HITS as i64,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.moves.maxHits",
+            moves.max_hits as i64,
+            2,
+            MAX_HITS as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.moves.minHits",
+            moves.min_hits as i64,
+            "randomizer.moves.maxHits",
+            moves.max_hits as i64,
+        );
+
+        let charisma = &self.randomizer.charisma;
+
+        in_range(
+            &mut errors,
+            "randomizer.charisma.multiplier",
+            charisma.multiplier as i64,
+            1,
+            1000,
+        );
+
+        let card_game = &self.randomizer.card_game;
+
+        in_range(
+            &mut errors,
+            "randomizer.cardGame.minCardBuyPrice",
+            card_game.min_card_buy_price,
+            0,
+            i16::MAX as i64,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.cardGame.maxCardBuyPrice",
+            card_game.max_card_buy_price,
+            0,
+            i16::MAX as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.cardGame.minCardBuyPrice",
+            card_game.min_card_buy_price,
+            "randomizer.cardGame.maxCardBuyPrice",
+            card_game.max_card_buy_price,
+        );
+
+        let parties = &self.randomizer.parties;
+
+        in_range(
+            &mut errors,
+            "randomizer.parties.startingRookie",
+            parties.starting_rookie as i64,
+            0,
+            dmw3_consts::ROOKIE_COUNT as i64 - 1,
+        );
+
+        total(
+            &mut errors,
+            "randomizer.parties.totalStartingStats",
+            parties.total_starting_stats as i64,
+            "randomizer.parties.minStartingStat",
+            parties.min_starting_stat as i64,
+            5,
+        );
+        total(
+            &mut errors,
+            "randomizer.parties.totalStartingRes",
+            parties.total_starting_res as i64,
+            "randomizer.parties.minStartingRes",
+            parties.min_starting_res as i64,
+            5,
+        );
+        total(
+            &mut errors,
+            "randomizer.parties.dvTotalStats",
+            parties.dv_total_stats as i64,
+            "randomizer.parties.dvMinStat",
+            parties.dv_min_stat as i64,
+            5,
+        );
+        total(
+            &mut errors,
+            "randomizer.parties.dvTotalRes",
+            parties.dv_total_res as i64,
+            "randomizer.parties.dvMinRes",
+            parties.dv_min_res as i64,
+            7,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.parties.dvStageScaling",
+            parties.dv_stage_scaling as i64,
+            0,
+            1000,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.parties.minAffinity",
+            parties.min_affinity as i64,
+            dmw3_consts::MIN_STAT_AFFINITY as i64,
+            dmw3_consts::MAX_STAT_AFFINITY as i64,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.parties.maxAffinity",
+            parties.max_affinity as i64,
+            dmw3_consts::MIN_STAT_AFFINITY as i64,
+            dmw3_consts::MAX_STAT_AFFINITY as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.parties.minAffinity",
+            parties.min_affinity as i64,
+            "randomizer.parties.maxAffinity",
+            parties.max_affinity as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.parties.minExpModifier",
+            parties.min_exp_modifier as i64,
+            "randomizer.parties.maxExpModifier",
+            parties.max_exp_modifier as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.parties.minHpModifier",
+            parties.min_hp_modifier as i64,
+            "randomizer.parties.maxHpModifier",
+            parties.max_hp_modifier as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.parties.minMpModifier",
+            parties.min_mp_modifier as i64,
+            "randomizer.parties.maxMpModifier",
+            parties.max_mp_modifier as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.parties.minStartingHp",
+            parties.min_starting_hp as i64,
+            "randomizer.parties.maxStartingHp",
+            parties.max_starting_hp as i64,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.parties.minStartingMp",
+            parties.min_starting_mp as i64,
+            "randomizer.parties.maxStartingMp",
+            parties.max_starting_mp as i64,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.parties.minRequirementLevel",
+            parties.min_requirement_level as i64,
+            1,
+            99,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.parties.maxRequirementLevel",
+            parties.max_requirement_level as i64,
+            1,
+            99,
+        );
+        min_max(
+            &mut errors,
+            "randomizer.parties.minRequirementLevel",
+            parties.min_requirement_level as i64,
+            "randomizer.parties.maxRequirementLevel",
+            parties.max_requirement_level as i64,
+        );
+        in_range(
+            &mut errors,
+            "randomizer.parties.easyRequirementPercent",
+            parties.easy_requirement_percent as i64,
+            1,
+            100,
+        );
+
+        let item_pools = &self.randomizer.item_pools;
+
+        for (i, pool) in item_pools.iter().enumerate() {
+            if item_pools[..i].iter().any(|x| x.name == pool.name) {
+                errors.push(ValidationError::DuplicateName {
+                    path: format!("randomizer.itemPools.{i}.name"),
+                    name: pool.name.clone(),
+                });
+            }
+
+            item_refs(
+                &mut errors,
+                &format!("randomizer.itemPools.{i}.include"),
+                &pool.include,
+            );
+            item_refs(
+                &mut errors,
+                &format!("randomizer.itemPools.{i}.exclude"),
+                &pool.exclude,
+            );
+        }
+
+        pool_ref(
+            &mut errors,
+            "randomizer.shops.itemsOnly",
+            &self.randomizer.shops.items_only,
+            item_pools,
+        );
+        pool_ref(
+            &mut errors,
+            "randomizer.maps.itemBoxesItemsOnly",
+            &self.randomizer.maps.item_boxes_items_only,
+            item_pools,
+        );
+        pool_ref(
+            &mut errors,
+            "randomizer.auctions.auctionItemsPool",
+            &self.randomizer.auctions.auction_items_pool,
+            item_pools,
+        );
+        pool_ref(
+            &mut errors,
+            "randomizer.drops.itemsPool",
+            &self.randomizer.drops.items_pool,
+            item_pools,
+        );
+
+        for (i, rule) in self.randomizer.shops.price_rules.iter().enumerate() {
+            pool_ref(
+                &mut errors,
+                &format!("randomizer.shops.priceRules.{i}.pool"),
+                &rule.pool,
+                item_pools,
+            );
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset() -> Preset {
+        serde_json::from_str("{}").unwrap()
+    }
+
+    #[test]
+    fn default_preset_is_valid() {
+        assert_eq!(preset().validate(), Vec::new());
+    }
+
+    #[test]
+    fn min_above_max_flags_both_paths() {
+        let mut preset = preset();
+        preset.randomizer.encounters.min_team_size = 3;
+        preset.randomizer.encounters.max_team_size = 2;
+
+        let errors = preset.validate();
+
+        assert!(invalid(&errors, "randomizer.encounters.minTeamSize"));
+        assert!(invalid(&errors, "randomizer.encounters.maxTeamSize"));
+        assert!(!invalid(&errors, "randomizer.encounters.levelBand"));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut preset = preset();
+        preset.randomizer.drops.max_drop_rate = 101;
+
+        let errors = preset.validate();
+
+        assert!(errors.contains(&ValidationError::OutOfRange {
+            path: String::from("randomizer.drops.maxDropRate"),
+            value: 101,
+            min: 0,
+            max: 100,
+        }));
+    }
+
+    #[test]
+    fn total_below_minimum() {
+        let mut preset = preset();
+        preset.randomizer.parties.total_starting_stats = 10;
+        preset.randomizer.parties.min_starting_stat = 3;
+
+        let errors = preset.validate();
+
+        assert!(invalid(&errors, "randomizer.parties.totalStartingStats"));
+        assert!(invalid(&errors, "randomizer.parties.minStartingStat"));
+        assert_eq!(
+            errors
+                .iter()
+                .find(|x| x.paths().contains(&"randomizer.parties.totalStartingStats"))
+                .unwrap()
+                .to_string(),
+            "randomizer.parties.totalStartingStats: 10 is less than 5 * randomizer.parties.minStartingStat (15)"
+        );
+    }
+
+    #[test]
+    fn item_pools() {
+        let mut preset = preset();
+        preset.randomizer.item_pools = serde_json::from_str(
+            r#"[
+                { "name": "a", "include": ["Medicine", 5] },
+                { "name": "a", "exclude": ["", "0x1fffff"] }
+            ]"#,
+        )
+        .unwrap();
+        preset.randomizer.drops.items_pool = ShopItems::Custom(String::from("a"));
+        preset.randomizer.shops.items_only = ShopItems::Custom(String::from("b"));
+
+        let errors = preset.validate();
+
+        assert!(!invalid(&errors, "randomizer.itemPools.0.name"));
+        assert!(invalid(&errors, "randomizer.itemPools.1.name"));
+        assert!(!invalid(&errors, "randomizer.itemPools.0.include"));
+        assert!(invalid(&errors, "randomizer.itemPools.1.exclude"));
+        assert!(!invalid(&errors, "randomizer.drops.itemsPool"));
+        assert!(errors.contains(&ValidationError::UnknownPool {
+            path: String::from("randomizer.shops.itemsOnly"),
+            name: String::from("b"),
+        }));
+    }
+
+    #[test]
+    fn price_rules() {
+        let mut preset = preset();
+        preset.randomizer.shops.price_rules = serde_json::from_str(
+            r#"[
+                { "pool": "buyable" },
+                { "pool": "sellable", "minSellPrice": -5, "priceVariance": 101 },
+                { "pool": { "custom": "equipment" }, "minSellPrice": 500, "maxSellPrice": 400 }
+            ]"#,
+        )
+        .unwrap();
+
+        let errors = preset.validate();
+
+        assert!(!invalid(&errors, "randomizer.shops.priceRules.0.pool"));
+        assert!(!invalid(
+            &errors,
+            "randomizer.shops.priceRules.0.minSellPrice"
+        ));
+        assert!(invalid(
+            &errors,
+            "randomizer.shops.priceRules.1.minSellPrice"
+        ));
+        assert!(invalid(
+            &errors,
+            "randomizer.shops.priceRules.1.priceVariance"
+        ));
+        assert!(invalid(&errors, "randomizer.shops.priceRules.2.pool"));
+        assert!(invalid(
+            &errors,
+            "randomizer.shops.priceRules.2.maxSellPrice"
+        ));
+    }
+
+    #[test]
+    fn region_shops() {
+        let mut preset = preset();
+        preset.randomizer.shops.region_shops = vec![0, dmw3_consts::SHOPS_LEN as u8];
+
+        let errors = preset.validate();
+
+        assert_eq!(errors.len(), 1);
+        assert!(invalid(&errors, "randomizer.shops.regionShops"));
+    }
+
+    #[test]
+    fn report_lists_every_error() {
+        let mut preset = preset();
+        preset.randomizer.drops.min_drop_rate = 101;
+
+        let report = report(&preset.validate());
+
+        assert!(report.starts_with("invalid preset:\n"));
+        assert!(report.contains("randomizer.drops.minDropRate: 101 is outside of [0, 100]"));
+        assert!(report.contains("randomizer.drops.minDropRate: 101 is greater than"));
+    }
+}
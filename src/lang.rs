@@ -71,6 +71,15 @@ impl Language {
         result
     }
 
+    pub fn decode(&self, text: &[u8]) -> String {
+        let trimmed = &text[..text.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1)];
+
+        match dmw3_lang::String::decode(trimmed) {
+            Ok(decoded) => decoded.to_string(),
+            Err(_) => String::from_utf8_lossy(trimmed).to_string(),
+        }
+    }
+
     pub fn to_file_name(&self, file_name: &str) -> String {
         format!("{}{}", self.to_prefix(), file_name)
     }
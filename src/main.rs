@@ -1,20 +1,24 @@
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use clap::Parser;
 
 mod cli;
 mod json;
 mod mkpsxiso;
 
+mod batch;
 mod db;
 mod dump;
+mod hash;
 mod lang;
 mod objects;
 mod rand;
+mod spoiler;
 mod util;
+mod workspace;
 use rand::patch;
 use tokio::runtime::Runtime;
 
-use crate::{dump::create_spoiler, modding::handle_mod};
+use crate::{dump::create_spoiler, modding::handle_mod, workspace::Workspace};
 
 mod gui;
 mod modding;
@@ -26,18 +30,41 @@ fn main() -> anyhow::Result<()> {
 
     let rt = Runtime::new()?;
 
-    if let Some(cli::Command::Mod { action }) = &cli.command {
-        return rt.block_on(handle_mod(action));
+    match &cli.command {
+        Some(cli::Command::Mod { action }) => return rt.block_on(handle_mod(action)),
+        Some(cli::Command::Batch {
+            path,
+            preset,
+            seeds,
+            count,
+            output_root,
+        }) => return rt.block_on(batch::batch(path, preset, seeds, *count, output_root)),
+        None => {}
     }
 
     let args = cli.args;
 
+    if args.normalize_preset {
+        let path = args
+            .preset
+            .as_ref()
+            .context("--normalize-preset requires --preset")?;
+
+        json::save_preset(path, &json::load_preset(&args.preset)?)?;
+
+        println!("normalized {}", path.display());
+
+        return Ok(());
+    }
+
     if let Some(path) = &args.path {
         rt.block_on(async {
             if args.dump {
-                mkpsxiso::extract(path).await?;
+                let workspace = Workspace::new(path).await?;
 
-                dump::dump(path).await?;
+                mkpsxiso::extract(&workspace).await?;
+
+                dump::dump(&workspace).await?;
             }
 
             Ok::<(), anyhow::Error>(())
@@ -46,33 +73,39 @@ fn main() -> anyhow::Result<()> {
 
     match &args.path {
         Some(path) => rt.block_on(async {
-            let mut preset = json::load_preset(&args.preset);
+            let mut preset = json::load_preset(&args.preset)?;
 
             preset.randomizer.seed = match &args.seed {
                 Some(seed) => *seed,
                 None => preset.randomizer.seed,
             };
 
+            let errors = preset.validate();
+            if !errors.is_empty() {
+                return Err(anyhow!(json::report(&errors)));
+            }
+
             let file_name = match args.output {
                 Some(name) => name,
                 None => format!("{}", preset.randomizer.seed),
             };
 
-            mkpsxiso::extract(path).await?;
+            let workspace = Workspace::new(path).await?;
+
+            mkpsxiso::extract(&workspace).await?;
+
+            let objects = patch(&workspace, &preset).await?;
 
-            let rom_name = path
-                .file_name()
-                .context("Failed file name get")?
-                .to_str()
-                .context("Failed to_str conversion")?;
+            let seed_hash = hash::seed_hash(&preset, &objects)?;
 
-            let objects = patch(path, &preset).await?;
+            create_spoiler(&objects, &workspace, &seed_hash).await?;
 
-            create_spoiler(&objects, path, file_name.as_str()).await?;
+            mkpsxiso::build(&workspace).await?;
 
-            mkpsxiso::build(rom_name, &file_name).await?;
+            let destination = workspace.publish(&args.output_root, &file_name).await?;
 
-            println!("randomized into {file_name}");
+            println!("randomized into {}", destination.display());
+            println!("seed hash {seed_hash}");
 
             Ok(())
         }),
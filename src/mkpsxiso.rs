@@ -1,9 +1,11 @@
-use anyhow::{anyhow, Context};
+use anyhow::anyhow;
 use async_std::fs;
 use quick_xml::de::from_str;
 use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 
+use crate::workspace::Workspace;
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", rename = "iso_project")]
 pub struct IsoProject {
@@ -228,23 +230,17 @@ async fn find_bin(name: &str) -> anyhow::Result<String> {
     Err(anyhow::anyhow!("Can't find {}", name))
 }
 
-pub async fn extract(path: &std::path::PathBuf) -> anyhow::Result<()> {
+pub async fn extract(workspace: &Workspace) -> anyhow::Result<()> {
     let bin = find_bin("dumpsxiso").await?;
 
-    let file_name = path
-        .file_name()
-        .context("failed to get file_name")?
-        .to_str()
-        .context("failed to convert file name to string")?;
-
     let output = Command::new(bin)
         .arg("-x")
-        .arg(format!("extract/{}/", file_name))
+        .arg(format!("{}/", workspace.extract_dir()))
         .arg("-s")
-        .arg(format!("extract/{}/out.xml", file_name))
+        .arg(format!("{}/out.xml", workspace.extract_dir()))
         .arg("-pt")
         .arg("-l")
-        .arg(path)
+        .arg(&workspace.rom)
         .output()
         .await?;
 
@@ -295,13 +291,8 @@ pub struct LbaLog {
     pub entries: Vec<Entry>,
 }
 
-async fn parse_lba_log(path: &std::path::PathBuf) -> anyhow::Result<LbaLog> {
-    let path_to_lba = path
-        .file_name()
-        .context("failed to get file_name")?
-        .to_str()
-        .map(|x| format!("extract/{}/lba.txt", x))
-        .context("failed to convert file name to string")?;
+async fn parse_lba_log(workspace: &Workspace) -> anyhow::Result<LbaLog> {
+    let path_to_lba = format!("{}/lba.txt", workspace.extract_dir());
 
     let content = fs::read_to_string(path_to_lba).await?;
     let mut log = LbaLog {
@@ -407,33 +398,22 @@ async fn parse_lba_log(path: &std::path::PathBuf) -> anyhow::Result<LbaLog> {
     Ok(log)
 }
 
-pub async fn xml_file(path: &std::path::PathBuf) -> anyhow::Result<IsoProject> {
-    let path_to_xml = path
-        .file_name()
-        .context("failed to get file_name")?
-        .to_str()
-        .map(|x| format!("extract/{}/out.xml", x))
-        .context("failed to convert file name to string")?;
+pub async fn xml_file(workspace: &Workspace) -> anyhow::Result<IsoProject> {
+    let path_to_xml = format!("{}/out.xml", workspace.extract_dir());
 
     let xml = fs::read_to_string(path_to_xml).await?;
 
     Ok(from_str(&xml)?)
 }
 
-pub async fn get_lba(path: &std::path::PathBuf) -> anyhow::Result<LbaLog> {
+pub async fn get_lba(workspace: &Workspace) -> anyhow::Result<LbaLog> {
     let binf = find_bin("mkpsxiso").await?;
 
-    let file_name = path
-        .file_name()
-        .context("failed to get file_name")?
-        .to_str()
-        .context("failed to convert file name to string")?;
-
     let output = Command::new(binf)
-        .arg(format!("extract/{}/new.xml", file_name))
+        .arg(format!("{}/new.xml", workspace.extract_dir()))
         .arg("-y")
         .arg("-lba")
-        .arg(format!("extract/{}/lba.txt", file_name))
+        .arg(format!("{}/lba.txt", workspace.extract_dir()))
         .arg("-noisogen")
         .output()
         .await?;
@@ -442,21 +422,21 @@ pub async fn get_lba(path: &std::path::PathBuf) -> anyhow::Result<LbaLog> {
         return Err(anyhow!(String::from_utf8_lossy(&output.stdout).to_string()));
     }
 
-    parse_lba_log(path).await
+    parse_lba_log(workspace).await
 }
 
-pub async fn build(rom_name: &str, file_name: &str) -> anyhow::Result<()> {
+pub async fn build(workspace: &Workspace) -> anyhow::Result<()> {
     let binf = find_bin("mkpsxiso").await?;
 
-    let bin = format!("randomized/{}/{}/new.bin", rom_name, file_name);
-    let cue = format!("randomized/{}/{}/new.cue", rom_name, file_name);
+    let bin = format!("{}/new.bin", workspace.build_dir());
+    let cue = format!("{}/new.cue", workspace.build_dir());
 
     let output = Command::new(binf)
         .arg("-o")
         .arg(&bin)
         .arg("-c")
         .arg(&cue)
-        .arg(format!("extract/{}/new.xml", rom_name))
+        .arg(format!("{}/new.xml", workspace.extract_dir()))
         .arg("-y")
         .output()
         .await?;
@@ -1,8 +1,7 @@
 use std::path::PathBuf;
 
-use anyhow::Context as _;
 use async_std::{
-    fs::{self, create_dir_all, File},
+    fs::{self, File},
     io::WriteExt,
 };
 
@@ -14,22 +13,20 @@ use crate::{
         read_model_objects, read_objects, read_sector_offsets, read_stage_pointer, read_text_files,
         write_objects, Objects,
     },
+    workspace::Workspace,
 };
 
 async fn extract(path: &PathBuf) -> anyhow::Result<()> {
-    mkpsxiso::extract(path).await?;
+    // mod tools keep the extracted files around for editing between commands
+    let workspace = Workspace::persistent(path, "rebuilt").await?;
 
-    let objects = read_objects(path).await?;
+    mkpsxiso::extract(&workspace).await?;
 
-    let serialized = serde_json::to_string_pretty(&objects)?;
+    let objects = read_objects(&workspace).await?;
 
-    let rom_name = path
-        .file_name()
-        .context("Failed to get file name")?
-        .to_str()
-        .context("Failed to convert to str")?;
+    let serialized = serde_json::to_string_pretty(&objects)?;
 
-    let mut json_file = File::create(format!("extract/{}/objects.json", rom_name)).await?;
+    let mut json_file = File::create(format!("{}/objects.json", workspace.extract_dir())).await?;
 
     json_file.write_all(serialized.as_bytes()).await?;
 
@@ -37,37 +34,35 @@ async fn extract(path: &PathBuf) -> anyhow::Result<()> {
 }
 
 async fn rebuild(path: &PathBuf) -> anyhow::Result<()> {
-    let rom_name = path
-        .file_name()
-        .context("Failed to get file name")?
-        .to_str()
-        .context("Failed to convert to str")?;
+    let workspace = Workspace::persistent(path, "rebuilt").await?;
+    let extract_dir = workspace.extract_dir();
 
-    let json = fs::read_to_string(format!("extract/{}/objects.json", rom_name)).await?;
+    let json = fs::read_to_string(format!("{}/objects.json", extract_dir)).await?;
 
     let mut objects: Objects = serde_json::from_str(&json)?;
 
-    objects.executable = read_executable(rom_name).await?;
-    objects.bufs = read_bufs(rom_name, &objects.executable).await?;
+    objects.executable = read_executable(extract_dir).await?;
+    objects.bufs = read_bufs(extract_dir, &objects.executable).await?;
     objects.stage = read_stage_pointer(&objects.bufs);
     (objects.sector_offsets, objects.file_sizes, _) =
         read_sector_offsets(&objects.bufs, &objects.executable)?;
-    (objects.iso_project, objects.file_map) = read_iso_project(path).await?;
-    objects.cargo_tower_text = read_cargo_tower_text(rom_name, &objects.executable).await?;
-    objects.text_files = read_text_files(rom_name, &objects.executable).await?;
-    objects.items = read_items(rom_name, &objects.executable).await?;
+    (objects.iso_project, objects.file_map) = read_iso_project(&workspace).await?;
+    objects.cargo_tower_text = read_cargo_tower_text(extract_dir, &objects.executable).await?;
+    objects.text_files = read_text_files(extract_dir, &objects.executable).await?;
+    objects.items = read_items(extract_dir, &objects.executable).await?;
 
     objects.model_objects =
-        read_model_objects(path, objects.executable.to_model_path(), "M").await?;
-    objects.stage_model_objects =
-        read_model_objects(path, objects.executable.to_stage_model_path(), "MEFT1").await?;
+        read_model_objects(&workspace, objects.executable.to_model_path(), "M").await?;
+    objects.stage_model_objects = read_model_objects(
+        &workspace,
+        objects.executable.to_stage_model_path(),
+        "MEFT1",
+    )
+    .await?;
 
     for map_object in &mut objects.map_objects {
-        map_object.buf = fs::read(format!(
-            "extract/{}/AAA/PRO/{}",
-            rom_name, map_object.file_name
-        ))
-        .await?;
+        map_object.buf =
+            fs::read(format!("{}/AAA/PRO/{}", extract_dir, map_object.file_name)).await?;
 
         if let Some(map_entities) = &mut map_object.entities {
             map_entities.entities.index = map_entities.entities_idx;
@@ -77,13 +72,11 @@ async fn rebuild(path: &PathBuf) -> anyhow::Result<()> {
         }
     }
 
-    write_objects(path, &mut objects).await?;
-
-    fix_lba(path, &mut objects).await?;
+    write_objects(&workspace, &mut objects).await?;
 
-    create_dir_all(format!("randomized/{}/{}", rom_name, "rebuilt")).await?;
+    fix_lba(&workspace, &mut objects).await?;
 
-    mkpsxiso::build(rom_name, "rebuilt").await?;
+    mkpsxiso::build(&workspace).await?;
 
     Ok(())
 }
@@ -31,7 +31,6 @@ use serde::Serialize;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::io::{Cursor, Seek, Write};
-use std::path::PathBuf;
 
 use crate::lang::Language;
 use crate::mkpsxiso;
@@ -39,6 +38,7 @@ use crate::mkpsxiso::get_lba;
 use crate::mkpsxiso::xml_file;
 use crate::mkpsxiso::Entry;
 use crate::mkpsxiso::IsoProject;
+use crate::workspace::Workspace;
 
 use dmw3_structs::{
     DigivolutionConditions, DigivolutionData, EncounterData, EnemyStats, EntityData, EntityLogic,
@@ -104,6 +104,10 @@ pub struct MusicSet {
     pub index: usize,
 }
 
+// name tables in every country folder, next to the item names
+pub const DIGIMON_NAMES: &str = "DIGINAME.BIN";
+pub const MOVE_NAMES: &str = "WAZANAME.BIN";
+
 pub const AUCTION_ITEMS_SETS: [u16; 16] = [
     0x8b19, 0x8b1f, 0x8b1a, 0x8b20, 0x8489, 0x8495, 0x847c, 0x8462, 0x8ade, 0x8ae8, 0x8af4, 0x8af3,
     0x8b01, 0x8b0d, 0x8b02, 0x8b0f,
@@ -332,6 +336,8 @@ pub struct Objects {
     pub iso_project: IsoProject,
     #[serde(skip)]
     pub stage: Pointer,
+    #[serde(skip)]
+    pub overlay: Pointer,
 
     #[serde(skip)]
     pub cargo_tower_text: HashMap<Language, Vec<Packed>>,
@@ -383,6 +389,10 @@ pub struct Objects {
     pub text_files: HashMap<String, TextFileGroup>,
     #[serde(skip)]
     pub items: TextFileGroup,
+    #[serde(skip)]
+    pub digimon_names: TextFileGroup,
+    #[serde(skip)]
+    pub move_names: TextFileGroup,
 
     pub auction_items: ObjectArray<AuctionSet>,
 
@@ -1137,20 +1147,16 @@ fn read_stage_overrides(
 }
 
 async fn read_map_objects(
-    path: &PathBuf,
+    workspace: &Workspace,
     stage_load_data: &Vec<StageLoadData>,
     stage: &Pointer,
     file_map: &Vec<mkpsxiso::File>,
     sector_offsets: &Vec<u32>,
     executable: &Executable,
 ) -> anyhow::Result<Vec<MapObject>> {
-    let rom_name = path
-        .file_name()
-        .context("Failed to get file name")?
-        .to_str()
-        .context("Failed to convert to str")?;
+    let extract_dir = workspace.extract_dir();
 
-    let mut pro_folder = fs::read_dir(format!("extract/{}/AAA/PRO", rom_name)).await?;
+    let mut pro_folder = fs::read_dir(format!("{}/AAA/PRO", extract_dir)).await?;
 
     let mut result: Vec<MapObject> = Vec::new();
 
@@ -1163,7 +1169,7 @@ async fn read_map_objects(
             let file_name = file.file_name().into_string().ok()?;
             file_name.starts_with("WSTAG").as_option()?;
 
-            let buf = fs::read(format!("extract/{}/AAA/PRO/{}", rom_name, file_name))
+            let buf = fs::read(format!("{}/AAA/PRO/{}", extract_dir, file_name))
                 .await
                 .ok()?;
 
@@ -1492,20 +1498,16 @@ async fn read_map_objects(
 }
 
 async fn write_model_objects(
-    path: &PathBuf,
+    workspace: &Workspace,
     objects: &Vec<ModelObject>,
     model_path: &str,
 ) -> anyhow::Result<()> {
-    let rom_name = path
-        .file_name()
-        .context("Failed to get file name")?
-        .to_str()
-        .context("Failed to convert to str")?;
+    let extract_dir = workspace.extract_dir();
 
     for model in objects {
         let mut new_model = File::create(format!(
-            "extract/{}/{}/{}",
-            rom_name, model_path, model.file_name,
+            "{}/{}/{}",
+            extract_dir, model_path, model.file_name,
         ))
         .await?;
 
@@ -1518,17 +1520,13 @@ async fn write_model_objects(
 }
 
 pub async fn read_model_objects(
-    path: &PathBuf,
+    workspace: &Workspace,
     model_path: &str,
     start_str: &str,
 ) -> anyhow::Result<Vec<ModelObject>> {
-    let rom_name = path
-        .file_name()
-        .context("Failed to get file name")?
-        .to_str()
-        .context("Failed to convert to str")?;
+    let extract_dir = workspace.extract_dir();
 
-    let mut model_itr = fs::read_dir(format!("extract/{}/{}/", rom_name, model_path)).await?;
+    let mut model_itr = fs::read_dir(format!("{}/{}/", extract_dir, model_path)).await?;
 
     let mut result = Vec::new();
 
@@ -1601,23 +1599,15 @@ pub async fn read_model_objects(
     Ok(result)
 }
 
-pub async fn read_bufs(rom_name: &str, executable: &Executable) -> anyhow::Result<Bufs> {
+pub async fn read_bufs(extract_dir: &str, executable: &Executable) -> anyhow::Result<Bufs> {
     let (stats_buf, map_buf, main_buf, shops_buf, card_shops_buf, exp_buf, pack_select_buf) = tokio::try_join!(
-        fs::read(format!("extract/{}/{}", rom_name, dmw3_consts::STATS_FILE)),
-        fs::read(format!("extract/{}/{}", rom_name, dmw3_consts::MAP_FILE)),
-        fs::read(format!("extract/{}/{}", rom_name, executable.as_str())),
-        fs::read(format!("extract/{}/{}", rom_name, dmw3_consts::SHOPS_FILE)),
-        fs::read(format!(
-            "extract/{}/{}",
-            rom_name,
-            dmw3_consts::CARD_SHOPS_FILE
-        )),
-        fs::read(format!("extract/{}/{}", rom_name, dmw3_consts::EXP_FILE)),
-        fs::read(format!(
-            "extract/{}/{}",
-            rom_name,
-            dmw3_consts::PACK_SELECT_FILE
-        ))
+        fs::read(format!("{}/{}", extract_dir, dmw3_consts::STATS_FILE)),
+        fs::read(format!("{}/{}", extract_dir, dmw3_consts::MAP_FILE)),
+        fs::read(format!("{}/{}", extract_dir, executable.as_str())),
+        fs::read(format!("{}/{}", extract_dir, dmw3_consts::SHOPS_FILE)),
+        fs::read(format!("{}/{}", extract_dir, dmw3_consts::CARD_SHOPS_FILE)),
+        fs::read(format!("{}/{}", extract_dir, dmw3_consts::EXP_FILE)),
+        fs::read(format!("{}/{}", extract_dir, dmw3_consts::PACK_SELECT_FILE))
     )?;
 
     Ok(Bufs {
@@ -1674,8 +1664,8 @@ pub fn read_sector_offsets(
     Ok((sector_offsets_object, file_sizes_object, sector_offsets))
 }
 
-pub async fn read_executable(rom_name: &str) -> anyhow::Result<Executable> {
-    let mut itr = fs::read_dir(format!("extract/{}/", rom_name)).await?;
+pub async fn read_executable(extract_dir: &str) -> anyhow::Result<Executable> {
+    let mut itr = fs::read_dir(format!("{}/", extract_dir)).await?;
 
     let mut executable_opt = None;
     while let Some(x) = itr.next().await {
@@ -1699,24 +1689,21 @@ pub async fn read_executable(rom_name: &str) -> anyhow::Result<Executable> {
     executable_opt.context("Can't find extracted executable")
 }
 
-pub async fn read_iso_project(path: &PathBuf) -> anyhow::Result<(IsoProject, Vec<mkpsxiso::File>)> {
-    let iso_project = xml_file(path).await?;
+pub async fn read_iso_project(
+    workspace: &Workspace,
+) -> anyhow::Result<(IsoProject, Vec<mkpsxiso::File>)> {
+    let iso_project = xml_file(workspace).await?;
     let file_map = iso_project.flatten()?;
     Ok((iso_project, file_map))
 }
 
 pub async fn read_cargo_tower_text(
-    rom_name: &str,
+    extract_dir: &str,
     executable: &Executable,
 ) -> anyhow::Result<HashMap<Language, Vec<Packed>>> {
     let mut cargo_tower_text = HashMap::new();
     for lang in executable.languages() {
-        let file = fs::read(format!(
-            "extract/{}/{}",
-            rom_name,
-            lang.to_path("SDMG260.BIN")
-        ))
-        .await?;
+        let file = fs::read(format!("{}/{}", extract_dir, lang.to_path("SDMG260.BIN"))).await?;
 
         let unpacked_cutscenes = Packed::from(file);
 
@@ -1733,7 +1720,7 @@ pub async fn read_cargo_tower_text(
 }
 
 pub async fn read_text_files(
-    rom_name: &str,
+    extract_dir: &str,
     executable: &Executable,
 ) -> anyhow::Result<HashMap<String, TextFileGroup>> {
     let mut text_files: HashMap<String, TextFileGroup> = HashMap::new();
@@ -1743,7 +1730,7 @@ pub async fn read_text_files(
         for lang in executable.languages() {
             let fsname = lang.to_file_name(sname);
 
-            let file = fs::read(format!("extract/{}/{}", rom_name, lang.to_path(sname))).await?;
+            let file = fs::read(format!("{}/{}", extract_dir, lang.to_path(sname))).await?;
 
             let packed = Packed::from_text(file);
 
@@ -1767,22 +1754,28 @@ pub async fn read_text_files(
     Ok(text_files)
 }
 
-pub async fn read_items(rom_name: &str, executable: &Executable) -> anyhow::Result<TextFileGroup> {
-    let mut item_files: HashMap<Language, TextFile> = HashMap::new();
+pub async fn read_items(
+    extract_dir: &str,
+    executable: &Executable,
+) -> anyhow::Result<TextFileGroup> {
+    read_names(extract_dir, executable, dmw3_consts::ITEM_NAMES).await
+}
+
+pub async fn read_names(
+    extract_dir: &str,
+    executable: &Executable,
+    file_name: &str,
+) -> anyhow::Result<TextFileGroup> {
+    let mut name_files: HashMap<Language, TextFile> = HashMap::new();
 
     for lang in executable.languages() {
-        let fsname = lang.to_file_name(dmw3_consts::ITEM_NAMES);
+        let fsname = lang.to_file_name(file_name);
 
-        let file = fs::read(format!(
-            "extract/{}/{}",
-            rom_name,
-            lang.to_path(dmw3_consts::ITEM_NAMES)
-        ))
-        .await?;
+        let file = fs::read(format!("{}/{}", extract_dir, lang.to_path(file_name))).await?;
 
         let packed = Packed::from_text(file);
 
-        item_files.insert(
+        name_files.insert(
             *lang,
             TextFile {
                 file: packed,
@@ -1792,12 +1785,28 @@ pub async fn read_items(rom_name: &str, executable: &Executable) -> anyhow::Resu
     }
 
     Ok(TextFileGroup {
-        files: item_files,
+        files: name_files,
         mapped_items: HashMap::new(),
         overwritten: HashSet::new(),
     })
 }
 
+// only used for spoilers, ids are shown when a table is missing
+async fn read_spoiler_names(
+    extract_dir: &str,
+    executable: &Executable,
+    file_name: &str,
+) -> TextFileGroup {
+    match read_names(extract_dir, executable, file_name).await {
+        Ok(names) => names,
+        Err(err) => {
+            println!("failed to read {file_name}, spoilers show ids instead: {err}");
+
+            TextFileGroup::default()
+        }
+    }
+}
+
 pub fn read_stage_pointer(bufs: &Bufs) -> Pointer {
     let stage_address = Pointer {
         value: dmw3_consts::STAGE_ADDRESS,
@@ -1808,18 +1817,14 @@ pub fn read_stage_pointer(bufs: &Bufs) -> Pointer {
     )
 }
 
-pub async fn read_objects(path: &PathBuf) -> anyhow::Result<Objects> {
-    let rom_name = path
-        .file_name()
-        .context("Failed file name get")?
-        .to_str()
-        .context("Failed to_str conversion")?;
+pub async fn read_objects(workspace: &Workspace) -> anyhow::Result<Objects> {
+    let extract_dir = workspace.extract_dir();
 
-    let (iso_project, file_map) = read_iso_project(path).await?;
+    let (iso_project, file_map) = read_iso_project(workspace).await?;
 
-    let executable = read_executable(rom_name).await?;
+    let executable = read_executable(extract_dir).await?;
 
-    let bufs = read_bufs(rom_name, &executable).await?;
+    let bufs = read_bufs(extract_dir, &executable).await?;
 
     let overlay_address = Pointer {
         value: dmw3_consts::OVERLAY_ADDRESS,
@@ -2150,11 +2155,14 @@ pub async fn read_objects(path: &PathBuf) -> anyhow::Result<Objects> {
         stage_load_data_arr.push(stage_load_data);
     }
 
-    let items = read_items(rom_name, &executable).await?;
+    let items = read_items(extract_dir, &executable).await?;
 
-    let text_files = read_text_files(rom_name, &executable).await?;
+    let digimon_names = read_spoiler_names(extract_dir, &executable, DIGIMON_NAMES).await;
+    let move_names = read_spoiler_names(extract_dir, &executable, MOVE_NAMES).await;
 
-    let cargo_tower_text = read_cargo_tower_text(rom_name, &executable).await?;
+    let text_files = read_text_files(extract_dir, &executable).await?;
+
+    let cargo_tower_text = read_cargo_tower_text(extract_dir, &executable).await?;
 
     let screen_name_mapping_index = bufs
         .map_buf
@@ -2438,7 +2446,7 @@ pub async fn read_objects(path: &PathBuf) -> anyhow::Result<Objects> {
     };
 
     let map_objects = read_map_objects(
-        path,
+        workspace,
         &stage_load_data_arr,
         &stage,
         &file_map,
@@ -2447,9 +2455,9 @@ pub async fn read_objects(path: &PathBuf) -> anyhow::Result<Objects> {
     )
     .await?;
 
-    let model_objects = read_model_objects(path, executable.to_model_path(), "M").await?;
+    let model_objects = read_model_objects(workspace, executable.to_model_path(), "M").await?;
     let stage_model_objects =
-        read_model_objects(path, executable.to_stage_model_path(), "MEFT1").await?;
+        read_model_objects(workspace, executable.to_stage_model_path(), "MEFT1").await?;
 
     Ok(Objects {
         executable,
@@ -2461,7 +2469,7 @@ pub async fn read_objects(path: &PathBuf) -> anyhow::Result<Objects> {
         stage_model_objects,
         sector_offsets: sector_offsets_object,
         file_sizes: file_sizes_object,
-        // overlay_address_pointer: overlay,
+        overlay,
         bufs,
         enemy_stats: enemy_stats_object,
         encounters: encounters_object,
@@ -2497,6 +2505,8 @@ pub async fn read_objects(path: &PathBuf) -> anyhow::Result<Objects> {
 
         text_files,
         items,
+        digimon_names,
+        move_names,
 
         auction_items,
 
@@ -2507,15 +2517,11 @@ pub async fn read_objects(path: &PathBuf) -> anyhow::Result<Objects> {
 }
 
 async fn write_map_objects(
-    path: &PathBuf,
+    workspace: &Workspace,
     objects: &mut Vec<MapObject>,
     stage: &Pointer,
 ) -> anyhow::Result<()> {
-    let rom_name = path
-        .file_name()
-        .context("Failed to get file name")?
-        .to_str()
-        .context("Failed to convert to str")?;
+    let extract_dir = workspace.extract_dir();
 
     for object in objects {
         let buf = &mut object.buf;
@@ -2563,7 +2569,7 @@ async fn write_map_objects(
 
         // write file
         let mut new_file =
-            File::create(format!("extract/{}/AAA/PRO/{}", rom_name, object.file_name)).await?;
+            File::create(format!("{}/AAA/PRO/{}", extract_dir, object.file_name)).await?;
 
         new_file.write_all(buf).await?;
     }
@@ -2571,24 +2577,19 @@ async fn write_map_objects(
     Ok(())
 }
 
-pub async fn fix_lba(path: &PathBuf, objects: &mut Objects) -> anyhow::Result<()> {
+pub async fn fix_lba(workspace: &Workspace, objects: &mut Objects) -> anyhow::Result<()> {
     let mut new_xml = objects.iso_project.clone();
     new_xml.remove_offsets()?;
 
     let xml_string = quick_xml::se::to_string(&new_xml)?;
 
-    let xml_path = path
-        .file_name()
-        .context("failed to get file_name")?
-        .to_str()
-        .map(|x| format!("extract/{}/new.xml", x))
-        .context("failed to convert file name to string")?;
+    let xml_path = format!("{}/new.xml", workspace.extract_dir());
 
     let mut new_xml = async_std::fs::File::create(xml_path).await?;
     new_xml.write_all(&xml_string.into_bytes()[..]).await?;
     new_xml.sync_all().await?;
 
-    let lba = get_lba(path).await?;
+    let lba = get_lba(workspace).await?;
 
     for i in 0..objects.sector_offsets.modified.len() {
         let offset = objects.sector_offsets.modified[i];
@@ -2643,18 +2644,11 @@ pub async fn fix_lba(path: &PathBuf, objects: &mut Objects) -> anyhow::Result<()
         .write_buf(&mut objects.bufs.main_buf)?;
     objects.file_sizes.write_buf(&mut objects.bufs.main_buf)?;
 
-    let rom_name = path
-        .file_name()
-        .context("Failed to get file name")?
-        .to_str()
-        .context("Failed to convert to str")?;
-
-    let mut new_main_executable = async_std::fs::File::create(format!(
-        "extract/{}/{}",
-        rom_name,
-        objects.executable.as_str()
-    ))
-    .await?;
+    let extract_dir = workspace.extract_dir();
+
+    let mut new_main_executable =
+        async_std::fs::File::create(format!("{}/{}", extract_dir, objects.executable.as_str()))
+            .await?;
 
     new_main_executable
         .write_all(&objects.bufs.main_buf)
@@ -2663,9 +2657,10 @@ pub async fn fix_lba(path: &PathBuf, objects: &mut Objects) -> anyhow::Result<()
     Ok(())
 }
 
-pub async fn write_objects(path: &PathBuf, objects: &mut Objects) -> anyhow::Result<()> {
+pub async fn write_objects(workspace: &Workspace, objects: &mut Objects) -> anyhow::Result<()> {
     objects.enemy_stats.write_buf(&mut objects.bufs.stats_buf)?;
     objects.encounters.write_buf(&mut objects.bufs.map_buf)?;
+    objects.enemy_parties.write_buf(&mut objects.bufs.map_buf)?;
     objects.parties.write_buf(&mut objects.bufs.main_buf)?;
     objects.rookie_data.write_buf(&mut objects.bufs.main_buf)?;
     objects
@@ -2716,44 +2711,26 @@ pub async fn write_objects(path: &PathBuf, objects: &mut Objects) -> anyhow::Res
         .bits_subtracts
         .write_buf(&mut objects.bufs.main_buf)?;
 
-    let rom_name = path
-        .file_name()
-        .context("Failed to get file name")?
-        .to_str()
-        .context("Failed to convert to str")?;
-
-    let mut new_main_executable = File::create(format!(
-        "extract/{}/{}",
-        rom_name,
-        objects.executable.as_str()
-    ))
-    .await?;
+    let extract_dir = workspace.extract_dir();
+
+    let mut new_main_executable =
+        File::create(format!("{}/{}", extract_dir, objects.executable.as_str())).await?;
 
     let mut new_stats =
-        File::create(format!("extract/{}/{}", rom_name, dmw3_consts::STATS_FILE)).await?;
+        File::create(format!("{}/{}", extract_dir, dmw3_consts::STATS_FILE)).await?;
 
     let mut new_shops =
-        File::create(format!("extract/{}/{}", rom_name, dmw3_consts::SHOPS_FILE)).await?;
+        File::create(format!("{}/{}", extract_dir, dmw3_consts::SHOPS_FILE)).await?;
 
-    let mut new_card_shops = File::create(format!(
-        "extract/{}/{}",
-        rom_name,
-        dmw3_consts::CARD_SHOPS_FILE
-    ))
-    .await?;
+    let mut new_card_shops =
+        File::create(format!("{}/{}", extract_dir, dmw3_consts::CARD_SHOPS_FILE)).await?;
 
-    let mut new_exp =
-        File::create(format!("extract/{}/{}", rom_name, dmw3_consts::EXP_FILE)).await?;
+    let mut new_exp = File::create(format!("{}/{}", extract_dir, dmw3_consts::EXP_FILE)).await?;
 
-    let mut new_map =
-        File::create(format!("extract/{}/{}", rom_name, dmw3_consts::MAP_FILE)).await?;
+    let mut new_map = File::create(format!("{}/{}", extract_dir, dmw3_consts::MAP_FILE)).await?;
 
-    let mut new_pack_select = File::create(format!(
-        "extract/{}/{}",
-        rom_name,
-        dmw3_consts::PACK_SELECT_FILE
-    ))
-    .await?;
+    let mut new_pack_select =
+        File::create(format!("{}/{}", extract_dir, dmw3_consts::PACK_SELECT_FILE)).await?;
 
     for sname in objects.executable.text_files().iter() {
         for lang in objects.executable.languages() {
@@ -2766,7 +2743,7 @@ pub async fn write_objects(path: &PathBuf, objects: &mut Objects) -> anyhow::Res
                 .context("Failed to get language")?;
 
             let mut new_file =
-                File::create(format!("extract/{}/{}", rom_name, lang.to_path(sname))).await?;
+                File::create(format!("{}/{}", extract_dir, lang.to_path(sname))).await?;
 
             let bytes: Vec<u8> = text_file.file.clone().to_bytes_text();
 
@@ -2785,12 +2762,8 @@ pub async fn write_objects(path: &PathBuf, objects: &mut Objects) -> anyhow::Res
 
         let packed = Packed { files };
 
-        let mut new_file = File::create(format!(
-            "extract/{}/{}",
-            rom_name,
-            lang.to_path("SDMG260.BIN")
-        ))
-        .await?;
+        let mut new_file =
+            File::create(format!("{}/{}", extract_dir, lang.to_path("SDMG260.BIN"))).await?;
 
         let bytes: Vec<u8> = packed.into();
 
@@ -2817,16 +2790,16 @@ pub async fn write_objects(path: &PathBuf, objects: &mut Objects) -> anyhow::Res
         .write_all(&objects.bufs.pack_select_buf)
         .await?;
 
-    write_map_objects(path, &mut objects.map_objects, &objects.stage).await?;
+    write_map_objects(workspace, &mut objects.map_objects, &objects.stage).await?;
 
     write_model_objects(
-        path,
+        workspace,
         &objects.model_objects,
         objects.executable.to_model_path(),
     )
     .await?;
     write_model_objects(
-        path,
+        workspace,
         &objects.stage_model_objects,
         objects.executable.to_stage_model_path(),
     )
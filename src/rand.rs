@@ -1,51 +1,115 @@
 use rand_xoshiro::rand_core::RngCore;
 use rand_xoshiro::rand_core::SeedableRng;
 use rand_xoshiro::Xoshiro256StarStar;
-use std::path::PathBuf;
 
+use crate::json::BossStrategy;
+use crate::json::DvRequirements;
 use crate::json::Preset;
 use crate::json::TNTStrategy;
 use crate::objects::fix_lba;
 use crate::objects::read_objects;
 use crate::objects::write_objects;
 use crate::objects::Objects;
+use crate::workspace::Workspace;
 
 pub use dmw3_structs;
 
-mod auctions;
+pub mod auctions;
 mod card_game;
+mod charisma;
+mod drops;
 mod encounters;
+mod enemy_techs;
 mod fixes;
+// not used while randomizing until quest ranges / complex steps are decoded
+#[allow(dead_code)]
+mod logic;
 pub mod maps;
 mod models;
+mod moves;
 mod parties;
 mod party_exp_bits;
+mod requirements;
+mod resistances;
 mod scaling;
 mod shops;
+mod teams;
 
-pub async fn patch(path: &PathBuf, preset: &Preset) -> anyhow::Result<Objects> {
-    let mut objects = read_objects(path).await?;
+pub use moves::MAX_HITS;
+pub use resistances::MAX_RESISTANCE;
+pub use shops::REGION_SHOPS;
+pub use teams::MAX_TEAM_SIZE;
+
+pub async fn patch(workspace: &Workspace, preset: &Preset) -> anyhow::Result<Objects> {
+    let mut objects = read_objects(workspace).await?;
 
     let mut rng = Xoshiro256StarStar::seed_from_u64(preset.randomizer.seed);
 
+    // first, so scaling can still rewrite enemy attack power afterwards
+    if preset.randomizer.moves.enabled {
+        moves::patch(
+            &preset.randomizer.moves,
+            preset.randomizer.shuffles,
+            &mut objects,
+            &mut rng,
+        );
+    }
+
     if preset.randomizer.encounters.enabled {
         encounters::patch(&preset.randomizer, &mut objects, &mut rng)?;
+
+        if preset.randomizer.encounters.teams {
+            teams::patch(&preset.randomizer.encounters, &mut objects, &mut rng)?;
+        }
+
+        if preset.randomizer.encounters.enemy_techs {
+            enemy_techs::patch(
+                &preset.randomizer.encounters,
+                preset.randomizer.shuffles,
+                &mut objects,
+                &mut rng,
+            );
+        }
     }
 
     if preset.randomizer.parties.enabled {
         parties::patch(&preset.randomizer, &mut objects, &mut rng)?;
+
+        if preset.randomizer.parties.requirements != DvRequirements::Keep {
+            requirements::patch(&preset.randomizer.parties, &mut objects, &mut rng);
+        }
+    }
+
+    if preset.randomizer.charisma.enabled {
+        charisma::patch(&preset.randomizer.charisma, &mut objects, &mut rng);
     }
 
     if preset.scaling.enabled {
         scaling::patch(&preset.scaling, &mut objects, &mut rng)?;
     }
 
+    if preset.randomizer.encounters.enabled
+        && preset.randomizer.encounters.bosses == BossStrategy::Separate
+    {
+        encounters::rescale_bosses(&preset.randomizer.encounters, &preset.scaling, &mut objects);
+    }
+
     if preset.fixes.scaling {
         fixes::scaling(&mut objects);
     }
 
+    // after scaling so the bounds apply to the final values
+    if preset.randomizer.encounters.enabled && preset.randomizer.encounters.resistances {
+        resistances::patch(&preset.randomizer.encounters, &mut objects, &mut rng);
+    }
+
     if preset.randomizer.shops.enabled {
-        shops::patch(&preset.randomizer.shops, &mut objects, &mut rng)?;
+        shops::patch(
+            &preset.randomizer.shops,
+            &preset.randomizer.item_pools,
+            &mut objects,
+            &mut rng,
+        )?;
     }
 
     if preset.randomizer.encounters.strategy == TNTStrategy::Ironmon {
@@ -53,7 +117,21 @@ pub async fn patch(path: &PathBuf, preset: &Preset) -> anyhow::Result<Objects> {
     }
 
     if preset.randomizer.auctions.enabled {
-        auctions::patch(&preset.randomizer.auctions, &mut objects, &mut rng)?;
+        auctions::patch(
+            &preset.randomizer.auctions,
+            &preset.randomizer.item_pools,
+            &mut objects,
+            &mut rng,
+        )?;
+    }
+
+    if preset.randomizer.drops.enabled {
+        drops::patch(
+            &preset.randomizer.drops,
+            &preset.randomizer.item_pools,
+            &mut objects,
+            &mut rng,
+        )?;
     }
 
     if preset.randomizer.card_game.enabled {
@@ -73,9 +151,9 @@ pub async fn patch(path: &PathBuf, preset: &Preset) -> anyhow::Result<Objects> {
     }
 
     // update all files on disk
-    write_objects(path, &mut objects).await?;
+    write_objects(workspace, &mut objects).await?;
 
-    fix_lba(path, &mut objects).await?;
+    fix_lba(workspace, &mut objects).await?;
 
     Ok(objects)
 }
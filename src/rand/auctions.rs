@@ -1,11 +1,16 @@
 use std::iter;
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use dmw3_consts::{AUCTION_COUNT, OINKMON_AUCTION_IDX};
 use dmw3_structs::ScriptConditionStep;
 use rand_xoshiro::{rand_core::RngCore, Xoshiro256StarStar};
 
-use crate::{json::Auction, lang::Language, objects::Objects, rand::shops::shoppable};
+use crate::{
+    json::{Auction, ItemPool},
+    lang::Language,
+    objects::Objects,
+    rand::shops::shoppable,
+};
 use dmw3_pack::Packed;
 
 fn encode_raw(s: &str) -> Vec<u8> {
@@ -562,8 +567,18 @@ pub fn oinkmon_pass_text(lang: Language) -> Packed {
     }
 }
 
-fn auction_items(preset: &Auction, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
-    let mut pool = shoppable(objects, &preset.auction_items_pool);
+fn auction_items(
+    preset: &Auction,
+    item_pools: &[ItemPool],
+    objects: &mut Objects,
+    rng: &mut Xoshiro256StarStar,
+) -> anyhow::Result<()> {
+    let mut pool = shoppable(objects, &preset.auction_items_pool, item_pools)?;
+
+    // every auction gets a different item
+    if pool.len() < objects.auction_items.modified.len() {
+        return Err(anyhow!("auction item pool is too small"));
+    }
 
     for auction_set in &mut objects.auction_items.modified {
         match &mut auction_set.item {
@@ -575,9 +590,11 @@ fn auction_items(preset: &Auction, objects: &mut Objects, rng: &mut Xoshiro256St
             ScriptConditionStep::EndStep => unreachable!("EndStep shouldn't be in mapped"),
         };
     }
+
+    Ok(())
 }
 
-fn full_prices(objects: &Objects) -> [[u32; 5]; 16] {
+pub fn full_prices(objects: &Objects) -> [[u32; 5]; 16] {
     let mut result = [[0; 5]; 16];
 
     for j in 0..5 {
@@ -667,11 +684,12 @@ fn auction_values(preset: &Auction, objects: &mut Objects, rng: &mut Xoshiro256S
 
 pub fn patch(
     preset: &Auction,
+    item_pools: &[ItemPool],
     objects: &mut Objects,
     rng: &mut Xoshiro256StarStar,
 ) -> anyhow::Result<()> {
     if preset.auction_items {
-        auction_items(preset, objects, rng);
+        auction_items(preset, item_pools, objects, rng)?;
     }
 
     if preset.auction_values {
@@ -0,0 +1,70 @@
+use rand_xoshiro::rand_core::RngCore;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use crate::json::{Charisma, CharismaReqs};
+use crate::rand::Objects;
+
+fn scale(value: u32, multiplier: u16) -> u32 {
+    ((value as u64 * multiplier as u64 / 100) as u32).max(1)
+}
+
+// tamer levels need an increasing amount of charisma, so requirements are
+// drawn up to the scaled vanilla maximum and sorted back into a curve
+fn random_requirements(
+    original: &[u32],
+    multiplier: u16,
+    rng: &mut Xoshiro256StarStar,
+) -> Vec<u32> {
+    let max = scale(original.iter().copied().max().unwrap_or(1), multiplier);
+
+    let mut requirements: Vec<u32> = original
+        .iter()
+        .map(|_| 1 + (rng.next_u64() % max as u64) as u32)
+        .collect();
+
+    requirements.sort();
+
+    for i in 1..requirements.len() {
+        requirements[i] = requirements[i].max(requirements[i - 1] + 1);
+    }
+
+    requirements
+}
+
+// only tamer requirements, rookie starting charisma and charisma affinity
+// aren't mapped in the rookie data yet (see Stat::Chr in parties)
+pub fn patch(preset: &Charisma, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
+    objects.charisma_reqs.modified = match preset.requirements {
+        CharismaReqs::Keep => objects.charisma_reqs.original.clone(),
+        CharismaReqs::Scale => objects
+            .charisma_reqs
+            .original
+            .iter()
+            .map(|x| scale(*x, preset.multiplier))
+            .collect(),
+        CharismaReqs::Random => {
+            random_requirements(&objects.charisma_reqs.original, preset.multiplier, rng)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_xoshiro::rand_core::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn random_requirements_increase() {
+        let original: Vec<u32> = (1..=15).map(|x| x * 20).collect();
+
+        for seed in 0..100 {
+            let mut rng = Xoshiro256StarStar::seed_from_u64(seed);
+            let requirements = random_requirements(&original, 50, &mut rng);
+
+            assert_eq!(requirements.len(), original.len());
+            assert!(requirements[0] >= 1);
+            assert!(requirements.windows(2).all(|x| x[0] < x[1]));
+        }
+    }
+}
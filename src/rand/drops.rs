@@ -0,0 +1,45 @@
+use anyhow::anyhow;
+use rand_xoshiro::rand_core::RngCore;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use crate::json::{Drops, ItemPool};
+use crate::rand::{shops::shoppable, Objects};
+
+fn keep(preset: &Drops, digimon_id: u16, droppable_item: u16) -> bool {
+    // tnt ball is needed for progression
+    droppable_item == dmw3_consts::TNT_BALL_ID
+        || (preset.keep_boss_drops && dmw3_consts::BOSSES.contains(&digimon_id))
+}
+
+pub fn patch(
+    preset: &Drops,
+    item_pools: &[ItemPool],
+    objects: &mut Objects,
+    rng: &mut Xoshiro256StarStar,
+) -> anyhow::Result<()> {
+    let pool = shoppable(objects, &preset.items_pool, item_pools)?;
+
+    if pool.is_empty() {
+        return Err(anyhow!("empty drop pool"));
+    }
+
+    let range = (preset.max_drop_rate - preset.min_drop_rate) as u64 + 1;
+
+    for stats in &mut objects.enemy_stats.modified {
+        if keep(preset, stats.digimon_id, stats.droppable_item) {
+            continue;
+        }
+
+        if preset.no_drops {
+            stats.droppable_item = 0;
+            stats.drop_rate = 0;
+
+            continue;
+        }
+
+        stats.droppable_item = pool[(rng.next_u64() % pool.len() as u64) as usize];
+        stats.drop_rate = preset.min_drop_rate + (rng.next_u64() % range) as u16;
+    }
+
+    Ok(())
+}
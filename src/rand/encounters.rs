@@ -1,16 +1,19 @@
 use std::collections::HashMap;
 
-use crate::json::{Encounters, Randomizer, TNTStrategy};
+use crate::json::{BossStrategy, EncounterTier, Encounters, Randomizer, Scaling, TNTStrategy};
 use crate::rand::{dmw3_structs::EncounterData, Objects};
 use crate::util::{self, uniform_random_vector, unique_vec};
 use anyhow::Context;
 use rand_xoshiro::Xoshiro256StarStar;
 
-fn skip(encounter: &EncounterData, preset: &Encounters) -> bool {
+pub fn is_boss(encounter: &EncounterData) -> bool {
+    dmw3_consts::BOSSES.contains(&(encounter.digimon_id as u16))
+}
+
+fn kept(encounter: &EncounterData, preset: &Encounters) -> bool {
     (!preset.cardmon
         && (dmw3_consts::CARDMON_MIN <= encounter.digimon_id as u16
             && encounter.digimon_id as u16 <= dmw3_consts::CARDMON_MAX))
-        || (!preset.bosses && dmw3_consts::BOSSES.contains(&(encounter.digimon_id as u16)))
         || (preset.strategy == TNTStrategy::Keep
             && encounter.digimon_id as u16 == dmw3_consts::TRICERAMON_ID
             && encounter.multiplier == 16)
@@ -21,39 +24,162 @@ fn skip(encounter: &EncounterData, preset: &Encounters) -> bool {
             && dmw3_consts::GALACTICMON_IDS.contains(&(encounter.digimon_id as u16)))
 }
 
-pub fn patch(
+pub fn skip(encounter: &EncounterData, preset: &Encounters) -> bool {
+    kept(encounter, preset) || (preset.bosses != BossStrategy::Mixed && is_boss(encounter))
+}
+
+// galacticmon (a different digimon every phase) and zanbamon are the fights
+// with several phases, they stay where they are so their phases stay together
+fn multi_phase(encounter: &EncounterData) -> bool {
+    dmw3_consts::GALACTICMON_IDS.contains(&(encounter.digimon_id as u16))
+        || encounter.digimon_id as u16 == dmw3_consts::ZANBAMON_ID
+}
+
+fn moved_boss(encounter: &EncounterData, preset: &Encounters) -> bool {
+    is_boss(encounter) && !kept(encounter, preset) && !multi_phase(encounter)
+}
+
+// multiplier that gives the boss the strength it would have at lv
+fn boss_multiplier(scaling: &Scaling, boss: &EncounterData, lv: u16) -> u16 {
+    let target = scaling.base_stats + scaling.stat_modifier * lv as i32;
+    let current = scaling.base_stats + scaling.stat_modifier * boss.lv as i32;
+
+    (boss.multiplier as i32 * target / current.max(1)).clamp(1, u16::MAX as i32) as u16
+}
+
+fn shuffle_bosses(preset: &Randomizer, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
+    let encounters = &objects.encounters.original;
+
+    // every encounter of a boss digimon moves as one
+    let mut fights: Vec<(u32, Vec<usize>)> = Vec::new();
+
+    for (i, encounter) in encounters.iter().enumerate() {
+        if !moved_boss(encounter, &preset.encounters) {
+            continue;
+        }
+
+        match fights.iter_mut().find(|(x, _)| *x == encounter.digimon_id) {
+            Some((_, indices)) => indices.push(i),
+            None => fights.push((encounter.digimon_id, vec![i])),
+        }
+    }
+
+    let mut donors: Vec<u32> = fights.iter().map(|(key, _)| *key).collect();
+
+    util::shuffle(&mut donors, preset.shuffles, rng);
+
+    for ((_, indices), donor) in fights.iter().zip(donors) {
+        for &i in indices {
+            let original = &encounters[i];
+
+            let boss = encounters
+                .iter()
+                .filter(|x| x.digimon_id == donor)
+                .min_by_key(|x| x.lv.abs_diff(original.lv))
+                .unwrap_or(original);
+
+            let mut encounter = *boss;
+
+            encounter.lv = original.lv;
+            encounter.max_hp = original.max_hp;
+
+            objects.encounters.modified[i] = encounter;
+        }
+    }
+}
+
+// runs after scaling, which rewrites every multiplier. without scaling the
+// boss is rescaled from its closest vanilla fight, with it from the lowest
+// level fight scaling based its stats on
+pub fn rescale_bosses(preset: &Encounters, scaling: &Scaling, objects: &mut Objects) {
+    for i in 0..objects.encounters.original.len() {
+        if !moved_boss(&objects.encounters.original[i], preset) {
+            continue;
+        }
+
+        let encounter = objects.encounters.modified[i];
+
+        let reference = match scaling.enabled {
+            true => objects
+                .encounters
+                .modified
+                .iter()
+                .filter(|x| x.digimon_id == encounter.digimon_id)
+                .min_by_key(|x| x.lv),
+            false => objects
+                .encounters
+                .original
+                .iter()
+                .filter(|x| x.digimon_id == encounter.digimon_id)
+                .min_by_key(|x| x.lv.abs_diff(encounter.lv)),
+        }
+        .copied()
+        .unwrap_or(encounter);
+
+        objects.encounters.modified[i].multiplier =
+            boss_multiplier(scaling, &reference, encounter.lv);
+    }
+}
+
+// rookie / champion / ultimate / mega, enemy only digimon have no stage
+pub fn stage(digimon_id: u16, rookies: &[u16]) -> Option<u32> {
+    if rookies.contains(&digimon_id) {
+        Some(0)
+    } else if dmw3_consts::CHAMPIONS.contains(&digimon_id) {
+        Some(1)
+    } else if dmw3_consts::ULTIMATES.contains(&digimon_id) {
+        Some(2)
+    } else if dmw3_consts::MEGAS.contains(&digimon_id)
+        || dmw3_consts::MEGAPLUS.contains(&digimon_id)
+        || dmw3_consts::ULTRAS.contains(&digimon_id)
+    {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+// encounters only get replaced by encounters from the same tier
+fn tier(encounter: &EncounterData, preset: &Encounters, rookies: &[u16]) -> u32 {
+    let level_band = (encounter.lv / preset.level_band.max(1)) as u32;
+
+    match preset.tier {
+        EncounterTier::Global => 0,
+        EncounterTier::Level => level_band,
+        // enemy only digimon fall back to level
+        EncounterTier::Stage => {
+            stage(encounter.digimon_id as u16, rookies).unwrap_or(4 + level_band)
+        }
+    }
+}
+
+fn shuffle_tier(
     preset: &Randomizer,
     objects: &mut Objects,
+    indices: &[usize],
     rng: &mut Xoshiro256StarStar,
 ) -> anyhow::Result<()> {
-    let len = objects.encounters.original.len();
-    let modified_encounters = &mut objects.encounters.modified;
-    let modified_enemy_stats = &mut objects.enemy_stats.modified;
     let encounters = &objects.encounters.original;
 
-    let possible_arr = unique_vec(
-        encounters
-            .iter()
-            .filter(|x| !skip(x, &preset.encounters))
-            .map(|x| x.digimon_id),
-    );
+    // global keeps every variant of a digimon, tiers only the ones in the tier
+    let variants: Vec<&EncounterData> = match preset.encounters.tier {
+        EncounterTier::Global => encounters.iter().collect(),
+        _ => indices.iter().map(|&i| &encounters[i]).collect(),
+    };
 
-    let skipped_count = encounters
-        .iter()
-        .filter(|x| skip(x, &preset.encounters))
-        .count();
+    let possible_arr = unique_vec(indices.iter().map(|&i| encounters[i].digimon_id));
 
     let mut shuffled_encounters_digimon: HashMap<u32, Vec<EncounterData>> = HashMap::new();
 
     let mut shuffled_ids =
-        util::uniform_random_vector(&possible_arr, len - skipped_count, preset.shuffles, rng);
+        util::uniform_random_vector(&possible_arr, indices.len(), preset.shuffles, rng);
 
     for digimon_id in possible_arr {
         let possible_encounters_arr = unique_vec(
-            encounters
+            variants
                 .iter()
                 .filter(|x| x.digimon_id == digimon_id)
-                .map(|x| x.clone()),
+                .map(|x| (*x).clone()),
         );
 
         let count = shuffled_ids.iter().filter(|x| **x == digimon_id).count();
@@ -64,20 +190,60 @@ pub fn patch(
         );
     }
 
-    for encounter in modified_encounters.iter_mut() {
-        if skip(encounter, &preset.encounters) {
-            continue;
-        };
-
+    for &i in indices {
         let new_encounter_id = shuffled_ids.pop().context("no ids left")?;
 
-        *encounter = shuffled_encounters_digimon
+        objects.encounters.modified[i] = shuffled_encounters_digimon
             .get_mut(&new_encounter_id)
             .context("failed to get mut ref")?
             .pop()
             .context("no encounters left")?;
     }
 
+    Ok(())
+}
+
+pub fn patch(
+    preset: &Randomizer,
+    objects: &mut Objects,
+    rng: &mut Xoshiro256StarStar,
+) -> anyhow::Result<()> {
+    let rookies: Vec<u16> = objects
+        .rookie_data
+        .original
+        .iter()
+        .map(|x| x.digimon_id)
+        .collect();
+
+    let mut tiers: Vec<u32> = Vec::new();
+    let mut tier_indices: HashMap<u32, Vec<usize>> = HashMap::new();
+
+    for (i, encounter) in objects.encounters.original.iter().enumerate() {
+        if skip(encounter, &preset.encounters) {
+            continue;
+        }
+
+        let tier = tier(encounter, &preset.encounters, &rookies);
+
+        if !tier_indices.contains_key(&tier) {
+            tiers.push(tier);
+        }
+
+        tier_indices.entry(tier).or_default().push(i);
+    }
+
+    for tier in tiers {
+        shuffle_tier(preset, objects, &tier_indices[&tier], rng)?;
+    }
+
+    if preset.encounters.bosses == BossStrategy::Separate {
+        shuffle_bosses(preset, objects, rng);
+    }
+
+    let modified_encounters = &objects.encounters.modified;
+    let modified_enemy_stats = &mut objects.enemy_stats.modified;
+    let encounters = &objects.encounters.original;
+
     if preset.encounters.strategy == TNTStrategy::Swap {
         let tric = modified_enemy_stats
             .iter()
@@ -0,0 +1,125 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::json::{Encounters, TechPool};
+use crate::rand::{dmw3_structs::MoveData, Objects};
+use crate::util::shuffle;
+use rand_xoshiro::rand_core::RngCore;
+use rand_xoshiro::Xoshiro256StarStar;
+
+// a tech shared by several enemies has one power, their target powers have
+// to be within this percent of each other
+const POWER_TOLERANCE: u64 = 25;
+
+fn hits(move_data: &MoveData) -> u32 {
+    match move_data.hit_effect == dmw3_consts::MULTI_HIT && move_data.freq > 1 {
+        true => move_data.freq as u32,
+        false => 1,
+    }
+}
+
+fn pool_key(preset: &Encounters, move_data: &MoveData) -> u32 {
+    match preset.enemy_tech_pool {
+        TechPool::Element => move_data.element as u32,
+        TechPool::HitEffect => move_data.hit_effect as u32,
+        TechPool::Any => 0,
+    }
+}
+
+fn close(a: u32, b: u32) -> bool {
+    let (a, b) = (a as u64, b as u64);
+
+    a * 100 <= b * (100 + POWER_TOLERANCE) && b * 100 <= a * (100 + POWER_TOLERANCE)
+}
+
+// every enemy draws an attack out of all damaging enemy techs with the same
+// pool key, the power is set so it deals as much as the tech it replaces
+pub fn patch(
+    preset: &Encounters,
+    shuffles: u8,
+    objects: &mut Objects,
+    rng: &mut Xoshiro256StarStar,
+) {
+    let moves = &objects.move_data.modified;
+
+    // the power of picked techs changes, so techs the party learns stay out
+    // of the pool unless an enemy already uses them
+    let learnable: HashSet<usize> = objects
+        .rookie_data
+        .original
+        .iter()
+        .chain(objects.digivolution_data.original.iter())
+        .flat_map(|x| x.tech.iter())
+        .filter(|&&x| x != 0)
+        .map(|&x| x as usize - 1)
+        .collect();
+
+    let used: HashSet<usize> = objects
+        .enemy_stats
+        .modified
+        .iter()
+        .filter(|x| x.attack != 0)
+        .map(|x| x.attack as usize - 1)
+        .collect();
+
+    let mut enemies: Vec<usize> = (0..objects.enemy_stats.modified.len())
+        .filter(|&i| {
+            let stats = &objects.enemy_stats.modified[i];
+
+            stats.attack != 0
+                && !dmw3_consts::BOSSES.contains(&stats.digimon_id)
+                && moves[stats.attack as usize - 1].power > 0
+        })
+        .collect();
+
+    // earlier enemies get the first pick of the techs
+    shuffle(&mut enemies, shuffles, rng);
+
+    // target power of every picked tech (by move data index), set by its
+    // first user
+    let mut targets: HashMap<usize, u32> = HashMap::new();
+
+    for i in enemies {
+        let old_attack = &moves[objects.enemy_stats.modified[i].attack as usize - 1];
+
+        let key = pool_key(preset, old_attack);
+        let total_power = old_attack.power as u32 * hits(old_attack);
+
+        let target = |tech: usize| total_power / hits(&moves[tech]);
+
+        let pool: Vec<usize> = (0..moves.len())
+            .filter(|&tech| {
+                moves[tech].power > 0
+                    && pool_key(preset, &moves[tech]) == key
+                    && (!learnable.contains(&tech) || used.contains(&tech))
+            })
+            .collect();
+
+        let fitting: Vec<usize> = pool
+            .iter()
+            .copied()
+            .filter(|x| match targets.get(x) {
+                Some(y) => close(*y, target(*x)),
+                None => true,
+            })
+            .collect();
+
+        // the enemy's own tech is always in the pool, if every tech is taken
+        // by enemies that need a different power the closest one is used
+        let tech = match fitting.is_empty() {
+            false => fitting[(rng.next_u64() % fitting.len() as u64) as usize],
+            true => *pool
+                .iter()
+                .min_by_key(|x| targets[x].abs_diff(target(**x)))
+                .unwrap(),
+        };
+
+        targets.entry(tech).or_insert(target(tech));
+
+        // attacks index move data from 1
+        objects.enemy_stats.modified[i].attack = (tech + 1) as _;
+    }
+
+    for (tech, target) in targets {
+        objects.move_data.modified[tech].power = target.clamp(1, u16::MAX as u32) as u16;
+    }
+}
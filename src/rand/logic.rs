@@ -0,0 +1,380 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use anyhow::anyhow;
+use dmw3_structs::ScriptConditionStep;
+use rand_xoshiro::rand_core::RngCore;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use crate::rand::{maps::type_script_add_item, Objects};
+use crate::util::shuffle;
+
+// progression model over map entity scripts
+//
+// every entity logic is a node that requires the tokens from its entity and
+// logic conditions and grants the tokens from its scripts. story progression
+// driven by quest_ranges / complex_steps isn't decoded yet (only the step id
+// of their structs is known), so a token that is required but never granted
+// by a map script can't be reasoned about and building the world fails
+// instead of guessing. until those are decoded nothing randomizes with it
+
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Token {
+    Item(u16),
+    Flag(u16),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Item(x) => write!(f, "item {x}"),
+            Token::Flag(x) => write!(f, "flag {x}"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Location {
+    pub map: usize,
+    pub entity: usize,
+    pub logic: usize,
+}
+
+struct Node {
+    location: Location,
+    requires: Vec<Token>,
+    grants: Vec<Token>,
+}
+
+pub struct Sweep {
+    pub collected: HashSet<Token>,
+    reached: HashSet<Location>,
+}
+
+impl Sweep {
+    pub fn reached(&self, location: Location) -> bool {
+        self.reached.contains(&location)
+    }
+}
+
+pub struct World {
+    nodes: Vec<Node>,
+    node_index: HashMap<Location, usize>,
+}
+
+pub fn token(step: &ScriptConditionStep) -> Option<Token> {
+    match step {
+        ScriptConditionStep::Step {
+            value,
+            condition_type,
+            flag,
+        } => match type_script_add_item(*condition_type) {
+            true => Some(Token::Item(*value)),
+            false => Some(Token::Flag(*flag as u16)),
+        },
+        ScriptConditionStep::EndStep => None,
+    }
+}
+
+impl World {
+    pub fn new(objects: &Objects) -> anyhow::Result<World> {
+        let mut nodes = Vec::new();
+
+        for (map_idx, map) in objects.map_objects.iter().enumerate() {
+            let Some(entities) = &map.entities else {
+                continue;
+            };
+
+            for (entity_idx, entity) in entities.mapped.iter().enumerate() {
+                for (logic_idx, logic) in entity.logics.iter().enumerate() {
+                    nodes.push(Node {
+                        location: Location {
+                            map: map_idx,
+                            entity: entity_idx,
+                            logic: logic_idx,
+                        },
+                        requires: entity
+                            .conditions
+                            .iter()
+                            .chain(logic.conditions.iter())
+                            .filter_map(token)
+                            .collect(),
+                        grants: logic.scripts.iter().filter_map(token).collect(),
+                    });
+                }
+            }
+        }
+
+        let granted: HashSet<Token> = nodes.iter().flat_map(|x| x.grants.clone()).collect();
+
+        let mut ungranted: Vec<Token> = nodes
+            .iter()
+            .flat_map(|x| x.requires.iter().copied())
+            .filter(|x| !granted.contains(x))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        if !ungranted.is_empty() {
+            ungranted.sort();
+
+            let tokens: Vec<String> = ungranted.iter().map(|x| x.to_string()).collect();
+
+            return Err(anyhow!(
+                "progression isn't modeled for: {}",
+                tokens.join(", ")
+            ));
+        }
+
+        Ok(World::from_nodes(nodes))
+    }
+
+    fn from_nodes(nodes: Vec<Node>) -> World {
+        let node_index = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x.location, i))
+            .collect();
+
+        World { nodes, node_index }
+    }
+
+    // items some entity checks for before it does anything
+    pub fn key_items(&self) -> HashSet<u16> {
+        self.nodes
+            .iter()
+            .flat_map(|x| x.requires.iter())
+            .filter_map(|x| match x {
+                Token::Item(item) => Some(*item),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn take(&mut self, location: Location, token: Token) {
+        if let Some(&i) = self.node_index.get(&location) {
+            self.nodes[i].grants.retain(|x| *x != token);
+        }
+    }
+
+    pub fn give(&mut self, location: Location, token: Token) {
+        if let Some(&i) = self.node_index.get(&location) {
+            self.nodes[i].grants.push(token);
+        }
+    }
+
+    pub fn sweep(&self) -> Sweep {
+        let mut collected = HashSet::new();
+        let mut reached = vec![false; self.nodes.len()];
+
+        loop {
+            let mut changed = false;
+
+            for (i, node) in self.nodes.iter().enumerate() {
+                if reached[i] {
+                    continue;
+                }
+
+                // conditions on tokens the node grants itself are state checks
+                // (opened box, talked to) rather than progression
+                let unlocked = node
+                    .requires
+                    .iter()
+                    .all(|x| collected.contains(x) || node.grants.contains(x));
+
+                if unlocked {
+                    reached[i] = true;
+                    changed = true;
+
+                    collected.extend(node.grants.iter().copied());
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        Sweep {
+            collected,
+            reached: self
+                .nodes
+                .iter()
+                .zip(reached)
+                .filter(|(_, x)| *x)
+                .map(|(node, _)| node.location)
+                .collect(),
+        }
+    }
+
+    pub fn unreachable(&self) -> Vec<Token> {
+        let sweep = self.sweep();
+
+        let mut unreachable: Vec<Token> = self
+            .nodes
+            .iter()
+            .flat_map(|x| x.requires.iter().copied())
+            .filter(|x| !sweep.collected.contains(x))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        unreachable.sort();
+
+        unreachable
+    }
+}
+
+pub fn check(objects: &Objects) -> anyhow::Result<()> {
+    let unreachable = World::new(objects)?.unreachable();
+
+    if unreachable.is_empty() {
+        return Ok(());
+    }
+
+    let tokens: Vec<String> = unreachable.iter().map(|x| x.to_string()).collect();
+
+    Err(anyhow!(
+        "world isn't completable, unreachable: {}",
+        tokens.join(", ")
+    ))
+}
+
+// places the key items found in slots into slots reachable with what has
+// been collected so far, then fills the rest from the pool. slots are the
+// locations granting an item and the item they grant in vanilla
+pub fn place(
+    world: &mut World,
+    slots: &[(Location, u16)],
+    pool: &[u16],
+    shuffles: u8,
+    rng: &mut Xoshiro256StarStar,
+) -> anyhow::Result<Vec<u16>> {
+    let key_items = world.key_items();
+
+    let mut keys: Vec<u16> = slots
+        .iter()
+        .map(|(_, item)| *item)
+        .filter(|x| key_items.contains(x))
+        .collect();
+
+    for (location, item) in slots {
+        world.take(*location, Token::Item(*item));
+    }
+
+    shuffle(&mut keys, shuffles, rng);
+
+    let mut values: Vec<Option<u16>> = vec![None; slots.len()];
+
+    for key in keys {
+        let sweep = world.sweep();
+
+        let open: Vec<usize> = (0..slots.len())
+            .filter(|&i| values[i].is_none() && sweep.reached(slots[i].0))
+            .collect();
+
+        if open.is_empty() {
+            return Err(anyhow!("no reachable slot left for item {key}"));
+        }
+
+        let i = open[(rng.next_u64() % open.len() as u64) as usize];
+
+        values[i] = Some(key);
+        world.give(slots[i].0, Token::Item(key));
+    }
+
+    Ok(values
+        .into_iter()
+        .map(|x| x.unwrap_or_else(|| pool[(rng.next_u64() % pool.len() as u64) as usize]))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_xoshiro::rand_core::SeedableRng;
+
+    use super::*;
+
+    fn location(map: usize) -> Location {
+        Location {
+            map,
+            entity: 0,
+            logic: 0,
+        }
+    }
+
+    fn node(map: usize, requires: &[Token], grants: &[Token]) -> Node {
+        Node {
+            location: location(map),
+            requires: requires.to_vec(),
+            grants: grants.to_vec(),
+        }
+    }
+
+    // map 0 is open and has a key, map 1 needs the key and has a flag, map 2
+    // needs the flag and has another box
+    fn world() -> World {
+        World::from_nodes(vec![
+            node(0, &[], &[Token::Item(1)]),
+            node(1, &[Token::Item(1)], &[Token::Flag(7), Token::Item(2)]),
+            node(2, &[Token::Flag(7)], &[Token::Item(3)]),
+        ])
+    }
+
+    #[test]
+    fn sweep_follows_requirements() {
+        let sweep = world().sweep();
+
+        assert!((0..3).all(|x| sweep.reached(location(x))));
+        assert!(world().unreachable().is_empty());
+    }
+
+    #[test]
+    fn sweep_stops_at_missing_tokens() {
+        let mut world = world();
+        world.take(location(0), Token::Item(1));
+
+        let sweep = world.sweep();
+
+        assert!(sweep.reached(location(0)));
+        assert!(!sweep.reached(location(1)));
+        assert!(!sweep.reached(location(2)));
+        assert_eq!(world.unreachable(), vec![Token::Item(1), Token::Flag(7)]);
+    }
+
+    #[test]
+    fn own_grants_are_state_checks() {
+        let world = World::from_nodes(vec![node(0, &[Token::Flag(3)], &[Token::Flag(3)])]);
+
+        assert!(world.sweep().reached(location(0)));
+    }
+
+    #[test]
+    fn place_keeps_keys_reachable() {
+        let slots = [(location(0), 1), (location(1), 2), (location(2), 3)];
+
+        for seed in 0..50 {
+            let mut world = world();
+            let mut rng = Xoshiro256StarStar::seed_from_u64(seed);
+
+            let values = place(&mut world, &slots, &[9], 1, &mut rng).unwrap();
+
+            // the only reachable box without the key has to hold it
+            assert_eq!(values[0], 1);
+            assert_eq!(&values[1..], &[9, 9]);
+            assert!(world.unreachable().is_empty());
+        }
+    }
+
+    #[test]
+    fn place_fails_without_reachable_slots() {
+        // the key is behind a box that needs the key
+        let mut world = World::from_nodes(vec![
+            node(0, &[Token::Item(1)], &[Token::Item(2)]),
+            node(1, &[Token::Item(2)], &[Token::Item(1)]),
+        ]);
+        let mut rng = Xoshiro256StarStar::seed_from_u64(0);
+
+        assert!(place(&mut world, &[(location(1), 1)], &[9], 1, &mut rng).is_err());
+    }
+}
@@ -2,8 +2,8 @@ use std::collections::{HashMap, HashSet};
 
 use crate::{
     json::{GroupStrategy, MusicPool},
-    objects::StageOverridesObject,
-    rand::{shops::shoppable, Objects},
+    objects::{MappedEntityLogic, StageOverridesObject},
+    rand::{logic::Location, shops::shoppable, Objects},
     util::{self, shuffle, uniform_random_vector, unique_vec},
 };
 use anyhow::{anyhow, Context};
@@ -152,128 +152,192 @@ fn backgrounds(
     Ok(())
 }
 
-fn item_boxes(
-    preset: &Randomizer,
+struct ItemBoxSlot {
+    location: Location,
+    script: usize,
+}
+
+fn item_box_slots(objects: &Objects) -> Vec<ItemBoxSlot> {
+    let mut slots = Vec::new();
+
+    for (map_idx, map) in objects.map_objects.iter().enumerate() {
+        let Some(entities) = &map.entities else {
+            continue;
+        };
+
+        for (entity_idx, mapped_entity) in entities.mapped.iter().enumerate() {
+            if !dmw3_consts::ITEM_BOX_SPRITES.contains(&mapped_entity.data.sprite)
+                || mapped_entity.logics.is_empty()
+            {
+                continue;
+            }
+
+            for (logic_idx, logic) in mapped_entity.logics.iter().enumerate() {
+                if logic.conversation == 0 {
+                    break;
+                }
+
+                for (script_idx, script) in logic.scripts.iter().enumerate() {
+                    let condition_type = match script {
+                        ScriptConditionStep::Step { condition_type, .. } => condition_type,
+                        ScriptConditionStep::EndStep => {
+                            unreachable!("EndStep shouldn't be in mapped")
+                        }
+                    };
+
+                    if !type_script_add_item(*condition_type) {
+                        continue;
+                    }
+
+                    slots.push(ItemBoxSlot {
+                        location: Location {
+                            map: map_idx,
+                            entity: entity_idx,
+                            logic: logic_idx,
+                        },
+                        script: script_idx,
+                    });
+
+                    break;
+                }
+            }
+        }
+    }
+
+    slots
+}
+
+fn item_box_logic<'a>(
+    objects: &'a mut Objects,
+    location: Location,
+) -> anyhow::Result<&'a mut MappedEntityLogic> {
+    objects.map_objects[location.map]
+        .entities
+        .as_mut()
+        .context("missing entities")?
+        .mapped[location.entity]
+        .logics
+        .get_mut(location.logic)
+        .context("missing logic")
+}
+
+// returns the conversation that announces the received item
+fn item_box_text(
     objects: &mut Objects,
-    rng: &mut Xoshiro256StarStar,
-) -> anyhow::Result<()> {
-    let pool: Vec<_> = shoppable(objects, &preset.maps.item_boxes_items_only)
-        .into_iter()
-        .collect();
+    talk_file: u16,
+    conversation: usize,
+    nv: u16,
+) -> anyhow::Result<usize> {
     let language = objects
         .executable
         .languages()
         .first()
         .context("executable with no languages")?;
 
-    for map in &mut objects.map_objects {
-        if let Some(entities) = &mut map.entities {
-            for mapped_entity in &mut entities.mapped {
-                if !dmw3_consts::ITEM_BOX_SPRITES.contains(&mapped_entity.data.sprite)
-                    || mapped_entity.logics.is_empty()
-                {
-                    continue;
-                }
-
-                for logic in &mut mapped_entity.logics {
-                    if logic.conversation == 0 {
-                        break;
-                    }
+    let real_file = objects
+        .file_map
+        .iter()
+        .find(|x| x.offs == Some(objects.sector_offsets.original[talk_file as usize]))
+        .context("failed to find real file")?;
+
+    let sname = &real_file.name[1..];
+
+    let group = objects
+        .text_files
+        .get_mut(sname)
+        .context("failed to get mut")?;
+
+    // alrady exists (rare)
+    if let Some(idx) = group.mapped_items.get(&nv) {
+        return Ok(*idx as usize);
+    }
+
+    if group.overwritten.contains(&(conversation as u32)) {
+        // index already overwritten
+        let idx = group
+            .files
+            .get(language)
+            .context("missing lang")?
+            .file
+            .files
+            .len();
+
+        for (lang, talk_file) in &mut group.files {
+            let item_name = objects
+                .items
+                .files
+                .get(lang)
+                .context("failed to get by lang")?
+                .file
+                .files[nv as usize]
+                .clone();
+
+            talk_file.file.files.push(lang.to_received_item(item_name));
+        }
 
-                    for script in &mut logic.scripts {
-                        let (value, condition_type, _) = match script {
-                            ScriptConditionStep::Step {
-                                value,
-                                condition_type,
-                                flag,
-                            } => (value, condition_type, flag),
-                            ScriptConditionStep::EndStep => {
-                                unreachable!("EndStep shouldn't be in mapped")
-                            }
-                        };
+        group.mapped_items.insert(nv, idx as u16);
 
-                        if !type_script_add_item(*condition_type) {
-                            continue;
-                        }
+        return Ok(idx);
+    }
 
-                        let nv = pool[(rng.next_u64() % pool.len() as u64) as usize];
+    // index is safe for overwrite
+    for (lang, talk_file) in &mut group.files {
+        let item_name = objects
+            .items
+            .files
+            .get(lang)
+            .context("failed to get by lang")?
+            .file
+            .files[nv as usize]
+            .clone();
 
-                        *value = nv;
+        talk_file.file.files[conversation] = lang.to_received_item(item_name);
+    }
 
-                        let real_file = objects
-                            .file_map
-                            .iter()
-                            .find(|x| {
-                                x.offs
-                                    == Some(objects.sector_offsets.original[map.talk_file as usize])
-                            })
-                            .context("failed to find real file")?;
+    group.overwritten.insert(conversation as u32);
+    group.mapped_items.insert(nv, conversation as u16);
 
-                        let sname = &real_file.name[1..];
+    Ok(conversation)
+}
 
-                        let group = objects
-                            .text_files
-                            .get_mut(sname)
-                            .context("failed to get mut")?;
+fn item_boxes(
+    preset: &Randomizer,
+    objects: &mut Objects,
+    rng: &mut Xoshiro256StarStar,
+) -> anyhow::Result<()> {
+    let pool: Vec<_> = shoppable(
+        objects,
+        &preset.maps.item_boxes_items_only,
+        &preset.item_pools,
+    )?
+    .into_iter()
+    .collect();
 
-                        // alrady exists (rare)
-                        if let Some(idx) = group.mapped_items.get(&nv) {
-                            logic.conversation = *idx as usize;
+    if pool.is_empty() {
+        return Err(anyhow!("empty item box pool"));
+    }
 
-                            break;
-                        }
+    let slots = item_box_slots(objects);
 
-                        if group.overwritten.contains(&(logic.conversation as u32)) {
-                            // index already overwritten
-                            let idx = group
-                                .files
-                                .get(language)
-                                .context("missing lang")?
-                                .file
-                                .files
-                                .len();
-
-                            for (lang, talk_file) in &mut group.files {
-                                let item_name = objects
-                                    .items
-                                    .files
-                                    .get(lang)
-                                    .context("failed to get by lang")?
-                                    .file
-                                    .files[nv as usize]
-                                    .clone();
-
-                                talk_file.file.files.push(lang.to_received_item(item_name));
-                            }
+    let values: Vec<u16> = slots
+        .iter()
+        .map(|_| pool[(rng.next_u64() % pool.len() as u64) as usize])
+        .collect();
 
-                            logic.conversation = idx;
-
-                            group.mapped_items.insert(nv, idx as u16);
-                        } else {
-                            // index is safe for overwrite
-                            for (lang, talk_file) in &mut group.files {
-                                let item_name = objects
-                                    .items
-                                    .files
-                                    .get(lang)
-                                    .context("failed to get by lang")?
-                                    .file
-                                    .files[nv as usize]
-                                    .clone();
-
-                                talk_file.file.files[logic.conversation as usize] =
-                                    lang.to_received_item(item_name);
-                            }
+    for (slot, nv) in slots.iter().zip(values) {
+        let talk_file = objects.map_objects[slot.location.map].talk_file;
 
-                            group.overwritten.insert(logic.conversation as u32);
-                            group.mapped_items.insert(nv, logic.conversation as u16);
-                        }
+        let logic = item_box_logic(objects, slot.location)?;
 
-                        break;
-                    }
-                }
-            }
+        if let ScriptConditionStep::Step { value, .. } = &mut logic.scripts[slot.script] {
+            *value = nv;
         }
+
+        let conversation = logic.conversation;
+
+        let conversation = item_box_text(objects, talk_file, conversation, nv)?;
+
+        item_box_logic(objects, slot.location)?.conversation = conversation;
     }
 
     Ok(())
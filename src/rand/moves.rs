@@ -0,0 +1,67 @@
+use rand_xoshiro::rand_core::RngCore;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use crate::json::Moves;
+use crate::rand::Objects;
+use crate::util::shuffle;
+
+pub const MAX_HITS: u8 = 8;
+
+pub fn patch(preset: &Moves, shuffles: u8, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
+    // techs without power are support techs
+    let attacks: Vec<usize> = (0..objects.move_data.original.len())
+        .filter(|&i| objects.move_data.original[i].power > 0)
+        .collect();
+
+    let variance = preset.power_variance as u64;
+
+    for &i in &attacks {
+        let original = &objects.move_data.original[i];
+        let move_data = &mut objects.move_data.modified[i];
+
+        let multi_hit = original.hit_effect == dmw3_consts::MULTI_HIT && original.freq > 1;
+
+        let hits = match multi_hit {
+            true => original.freq as u32,
+            false => 1,
+        };
+
+        // variance is applied to the total so extra hits never add damage
+        let factor = 100 - variance + rng.next_u64() % (variance * 2 + 1);
+        let total = (original.power as u64 * hits as u64 * factor / 100).max(1);
+
+        let new_hits = match multi_hit && preset.multi_hits {
+            true => {
+                let range = (preset.max_hits - preset.min_hits + 1) as u64;
+
+                preset.min_hits as u32 + (rng.next_u64() % range) as u32
+            }
+            false => hits,
+        };
+
+        if new_hits != hits {
+            move_data.freq = new_hits.try_into().unwrap_or(original.freq);
+        }
+
+        move_data.power = (total / new_hits as u64).clamp(1, u16::MAX as u64) as u16;
+
+        if preset.mp_from_power {
+            let mp = original.mp as u64 * total / (original.power as u64 * hits as u64);
+
+            move_data.mp = mp.max(1).try_into().unwrap_or(original.mp);
+        }
+    }
+
+    if preset.elements {
+        let mut elements: Vec<_> = attacks
+            .iter()
+            .map(|&i| objects.move_data.original[i].element)
+            .collect();
+
+        shuffle(&mut elements, shuffles, rng);
+
+        for (&i, element) in attacks.iter().zip(elements) {
+            objects.move_data.modified[i].element = element;
+        }
+    }
+}
@@ -3,9 +3,14 @@ use rand_xoshiro::rand_core::RngCore;
 use rand_xoshiro::Xoshiro256StarStar;
 
 use crate::rand::Objects;
-use crate::{json::Randomizer, util::unique_vec};
-
-use super::dmw3_structs::DigivolutionData;
+use crate::{
+    json::{AffinityMode, PartyMode, Randomizer},
+    util::unique_vec,
+};
+
+use super::dmw3_structs::{DigivolutionData, MoveData};
+use super::encounters::stage;
+use super::requirements;
 use crate::util::{self, uniform_random_vector};
 
 #[derive(Clone, Copy)]
@@ -84,9 +89,28 @@ pub fn patch(
 
         parties[8] = uniform[(rng.next_u64() % 6) as usize];
 
+        match preset.parties.party_mode {
+            PartyMode::Random => {}
+            PartyMode::Fixed => lead_packs(parties, preset.parties.starting_rookie),
+            PartyMode::RandomLeader => lead_packs(parties, (rng.next_u64() % 8) as u8),
+            // whichever pack gets picked, the player only ever has this rookie
+            PartyMode::Single => parties.fill((rng.next_u64() % 8) as u8),
+        }
+
         for i in 0..9 {
             objects.pack_previews.modified[i] = parties[i] as u32;
         }
+
+        // previews no longer tell which pack has which rookies
+        if preset.parties.random_previews {
+            let previews = uniform_random_vector(&all_digimon, 8, preset.shuffles, rng);
+
+            for i in 0..8 {
+                objects.pack_previews.modified[i] = previews[i] as u32;
+            }
+
+            objects.pack_previews.modified[8] = previews[(rng.next_u64() % 6) as usize] as u32;
+        }
     }
 
     if preset.parties.stat_distribution {
@@ -111,11 +135,13 @@ pub fn patch(
                         + preset.parties.min_starting_stat,
                 );
 
-                stats[i].set_affinity(
-                    rookie_data,
-                    ((before_normalization[i] * total_affinity) / (sum * 2)) as u8
-                        + dmw3_consts::MIN_STAT_AFFINITY,
-                );
+                if preset.parties.affinities == AffinityMode::Linked {
+                    stats[i].set_affinity(
+                        rookie_data,
+                        ((before_normalization[i] * total_affinity) / (sum * 2)) as u8
+                            + dmw3_consts::MIN_STAT_AFFINITY,
+                    );
+                }
             }
         }
     }
@@ -150,17 +176,27 @@ pub fn patch(
                         + preset.parties.min_starting_res,
                 );
 
-                resistances[i].set_affinity(
-                    rookie_data,
-                    ((before_normalization[i] * total_affinity) / (sum * 2)) as u8
-                        + dmw3_consts::MIN_STAT_AFFINITY,
-                );
+                if preset.parties.affinities == AffinityMode::Linked {
+                    resistances[i].set_affinity(
+                        rookie_data,
+                        ((before_normalization[i] * total_affinity) / (sum * 2)) as u8
+                            + dmw3_consts::MIN_STAT_AFFINITY,
+                    );
+                }
             }
         }
     }
 
+    if preset.parties.affinities == AffinityMode::Random {
+        random_affinities(preset, objects, rng);
+    }
+
+    if preset.parties.dv_stat_distribution || preset.parties.dv_res_distribution {
+        digivolution_stats(preset, objects, rng);
+    }
+
     if preset.parties.learned_tech {
-        learned_moves(objects, rng);
+        learned_moves(preset, objects, rng);
     }
 
     if preset.parties.signatures {
@@ -168,7 +204,9 @@ pub fn patch(
     }
 
     if preset.parties.digivolutions {
-        if preset.parties.keep_stages {
+        if preset.parties.shuffle_trees {
+            shuffle_trees(preset, objects, rng)?;
+        } else if preset.parties.keep_stages {
             dv_cond_limited(preset, objects, rng)?;
         } else {
             dv_cond_unlimited(preset, objects, rng)?;
@@ -256,7 +294,186 @@ fn hp_mp_balanced(objects: &mut Objects, rng: &mut Xoshiro256StarStar, preset: &
     }
 }
 
-fn learned_moves(objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
+// affinities rolled on their own, so high base stats don't mean fast growth
+fn random_affinities(preset: &Randomizer, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
+    let stats: Vec<Stat> = vec![
+        Stat::Str,
+        Stat::Def,
+        Stat::Spt,
+        Stat::Wis,
+        Stat::Spd,
+        Stat::FirRes,
+        Stat::WtrRes,
+        Stat::IceRes,
+        Stat::WndRes,
+        Stat::ThdRes,
+        Stat::MchRes,
+        Stat::DrkRes,
+    ];
+
+    let min = preset.parties.min_affinity;
+    let range = (preset.parties.max_affinity - min) as u64 + 1;
+
+    for rookie_data in &mut objects.rookie_data.modified {
+        for stat in &stats {
+            stat.set_affinity(rookie_data, min + (rng.next_u64() % range) as u8);
+        }
+    }
+}
+
+// splits total - min * len randomly over the stats, linked affinities follow
+// the same split like they do for rookies
+fn distribute(
+    preset: &Randomizer,
+    stats: &[Stat],
+    ddata: &mut DigivolutionData,
+    total: u16,
+    min: u16,
+    rng: &mut Xoshiro256StarStar,
+) {
+    let before_addition = total.saturating_sub(min * stats.len() as u16) as u64;
+
+    let affinity_range = dmw3_consts::MAX_STAT_AFFINITY - dmw3_consts::MIN_STAT_AFFINITY;
+    let total_affinity = (affinity_range * stats.len() as u8) as u64;
+
+    let before_normalization: Vec<u64> = stats.iter().map(|_| rng.next_u32() as u64).collect();
+
+    let sum: u64 = before_normalization.iter().sum::<u64>().max(1);
+
+    for (stat, value) in stats.iter().zip(before_normalization) {
+        stat.set(ddata, ((value * before_addition) / sum) as u16 + min);
+
+        if preset.parties.affinities == AffinityMode::Linked {
+            stat.set_affinity(
+                ddata,
+                ((value * total_affinity) / (sum * 2)) as u8 + dmw3_consts::MIN_STAT_AFFINITY,
+            );
+        }
+    }
+}
+
+// champions get the configured totals, every stage after that gets
+// dv_stage_scaling percent more
+fn digivolution_stats(preset: &Randomizer, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
+    let stats = [Stat::Str, Stat::Def, Stat::Spt, Stat::Wis, Stat::Spd];
+    let resistances = [
+        Stat::FirRes,
+        Stat::WtrRes,
+        Stat::IceRes,
+        Stat::WndRes,
+        Stat::ThdRes,
+        Stat::MchRes,
+        Stat::DrkRes,
+    ];
+
+    for ddata in &mut objects.digivolution_data.modified {
+        let stage = stage(ddata.digimon_id, &[]).unwrap_or(1).max(1) as u32;
+        let scale = 100 + (stage - 1) * preset.parties.dv_stage_scaling as u32;
+
+        let budget = |total: u16| (total as u32 * scale / 100).min(u16::MAX as u32) as u16;
+
+        if preset.parties.dv_stat_distribution {
+            distribute(
+                preset,
+                &stats,
+                ddata,
+                budget(preset.parties.dv_total_stats),
+                preset.parties.dv_min_stat,
+                rng,
+            );
+        }
+
+        if preset.parties.dv_res_distribution {
+            distribute(
+                preset,
+                &resistances,
+                ddata,
+                budget(preset.parties.dv_total_res),
+                preset.parties.dv_min_res,
+                rng,
+            );
+        }
+    }
+
+    if preset.parties.affinities != AffinityMode::Random {
+        return;
+    }
+
+    let min = preset.parties.min_affinity;
+    let range = (preset.parties.max_affinity - min) as u64 + 1;
+
+    for ddata in &mut objects.digivolution_data.modified {
+        for stat in stats.iter().chain(resistances.iter()) {
+            stat.set_affinity(ddata, min + (rng.next_u64() % range) as u8);
+        }
+    }
+}
+
+// every pack (3 slots each) starts with the leader, so it's in the party
+// whichever pack gets picked
+fn lead_packs(parties: &mut [u8], leader: u8) {
+    for pack in parties.chunks_mut(3) {
+        match pack.iter().position(|x| *x == leader) {
+            Some(i) => pack.swap(0, i),
+            None => pack[0] = leader,
+        }
+    }
+}
+
+// attacks are split into this many power tiers
+const TECH_TIERS: usize = 4;
+
+// how much more likely a matching tier / element is to be picked
+const TECH_WEIGHT: u64 = 4;
+
+struct TechInfo {
+    // None for support techs
+    tier: Option<usize>,
+    element: Option<u32>,
+}
+
+fn total_power(move_data: &MoveData) -> u32 {
+    match move_data.hit_effect == dmw3_consts::MULTI_HIT && move_data.freq > 1 {
+        true => move_data.power as u32 * move_data.freq as u32,
+        false => move_data.power as u32,
+    }
+}
+
+fn tech_info(moves: &[MoveData], powers: &[u32], tech: usize) -> TechInfo {
+    match tech
+        .checked_sub(1)
+        .and_then(|x| moves.get(x))
+        .filter(|x| x.power > 0)
+    {
+        Some(move_data) => TechInfo {
+            tier: Some(
+                powers.partition_point(|&x| x < total_power(move_data)) * TECH_TIERS
+                    / powers.len().max(1),
+            ),
+            element: Some(move_data.element as u32),
+        },
+        None => TechInfo {
+            tier: None,
+            element: None,
+        },
+    }
+}
+
+fn weighted_index(weights: &[u64], rng: &mut Xoshiro256StarStar) -> usize {
+    let mut roll = rng.next_u64() % weights.iter().sum::<u64>();
+
+    for (i, &weight) in weights.iter().enumerate() {
+        if roll < weight {
+            return i;
+        }
+
+        roll -= weight;
+    }
+
+    weights.len() - 1
+}
+
+fn learned_moves(preset: &Randomizer, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
     let mut learnable = Vec::new();
 
     for digivolution in &objects.digivolution_data.original {
@@ -270,19 +487,94 @@ fn learned_moves(objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
     }
 
     let learnable_arr = unique_vec(learnable);
-    for digivolution in &mut objects.digivolution_data.modified {
+
+    let moves = &objects.move_data.modified;
+
+    let mut powers: Vec<u32> = learnable_arr
+        .iter()
+        .filter_map(|&x| (x as usize).checked_sub(1).and_then(|x| moves.get(x)))
+        .filter(|x| x.power > 0)
+        .map(total_power)
+        .collect();
+
+    powers.sort();
+
+    let infos: Vec<TechInfo> = learnable_arr
+        .iter()
+        .map(|&x| tech_info(moves, &powers, x as usize))
+        .collect();
+
+    for (i, digivolution) in objects.digivolution_data.modified.iter_mut().enumerate() {
         let mut learnable_arr = learnable_arr.clone();
+        let mut infos: Vec<&TechInfo> = infos.iter().collect();
+
+        let original: Vec<TechInfo> = objects.digivolution_data.original[i]
+            .tech
+            .iter()
+            .map(|&x| tech_info(moves, &powers, x as usize))
+            .collect();
+
+        let elements: Vec<u32> = original.iter().filter_map(|x| x.element).collect();
+
+        let weight = |info: &TechInfo, slot: &TechInfo| {
+            let mut weight = 1;
 
-        for tech in &mut digivolution.tech {
+            if preset.parties.learned_tech_tier && info.tier == slot.tier {
+                weight *= TECH_WEIGHT;
+            }
+
+            if preset.parties.learned_tech_element
+                && info.element.is_some_and(|x| elements.contains(&x))
+            {
+                weight *= TECH_WEIGHT;
+            }
+
+            weight
+        };
+
+        for (t, tech) in digivolution.tech.iter_mut().enumerate() {
             if *tech == 0 {
                 continue;
             }
 
-            let mv = (rng.next_u64() % learnable_arr.len() as u64) as usize;
+            let weights: Vec<u64> = infos.iter().map(|&x| weight(x, &original[t])).collect();
+
+            let mv = weighted_index(&weights, rng);
 
             *tech = learnable_arr[mv];
             learnable_arr.remove(mv);
+            infos.remove(mv);
+        }
+
+        if !preset.parties.learned_tech_attack {
+            continue;
+        }
+
+        let has_attack = digivolution
+            .tech
+            .iter()
+            .any(|&x| tech_info(moves, &powers, x as usize).tier.is_some());
+
+        let slots: Vec<usize> = (0..digivolution.tech.len())
+            .filter(|&t| digivolution.tech[t] != 0)
+            .collect();
+
+        let attacks: Vec<usize> = (0..learnable_arr.len())
+            .filter(|&x| infos[x].tier.is_some())
+            .collect();
+
+        if has_attack || slots.is_empty() || attacks.is_empty() {
+            continue;
         }
+
+        let t = slots[(rng.next_u64() % slots.len() as u64) as usize];
+
+        let weights: Vec<u64> = attacks
+            .iter()
+            .map(|&x| weight(infos[x], &original[t]))
+            .collect();
+
+        digivolution.tech[t] = learnable_arr[attacks[weighted_index(&weights, rng)]];
     }
 }
 
@@ -314,6 +606,79 @@ fn signatues(objects: &mut Objects, rng: &mut Xoshiro256StarStar, preset: &Rando
     }
 }
 
+// builds every tree stage by stage from the requirements of all rookies,
+// prerequisites are redrawn from the same lower stages of the new tree
+fn shuffle_trees(
+    preset: &Randomizer,
+    objects: &mut Objects,
+    rng: &mut Xoshiro256StarStar,
+) -> anyhow::Result<()> {
+    // every tree holds the same digivolutions
+    let pool: Vec<u16> = objects.dv_cond.original[0]
+        .conditions
+        .iter()
+        .map(|x| x.index as u16)
+        .collect();
+
+    // requirements of every rookie, grouped by stage
+    let mut stage_conds = vec![Vec::new(); 5];
+    for conds in &objects.dv_cond.original {
+        for cond in &conds.conditions {
+            let stage =
+                requirements::stage(objects, cond.index as u16).context("failed to find stage")?;
+
+            stage_conds[stage].push(cond.clone());
+        }
+    }
+
+    for dindex in 0..dmw3_consts::ROOKIE_COUNT {
+        let mut conditions = objects.dv_cond.original[dindex].conditions.clone();
+
+        for (stage, conds) in stage_conds.iter().enumerate() {
+            let positions: Vec<usize> = (0..conditions.len())
+                .filter(|&i| {
+                    requirements::stage(objects, conditions[i].index as u16) == Some(stage)
+                })
+                .collect();
+
+            let mut targets: Vec<u32> = positions.iter().map(|&i| conditions[i].index).collect();
+            util::shuffle(&mut targets, preset.shuffles, rng);
+
+            for (&position, &target) in positions.iter().zip(targets.iter()) {
+                let mut cond = conds[(rng.next_u64() % conds.len() as u64) as usize].clone();
+
+                cond.index = target;
+
+                if cond.dv_index_1 > 0 {
+                    cond.dv_index_1 = requirements::random_prerequisite(
+                        objects,
+                        &pool,
+                        cond.dv_index_1,
+                        &[target as u16],
+                        rng,
+                    );
+                }
+
+                if cond.dv_index_2 > 0 {
+                    cond.dv_index_2 = requirements::random_prerequisite(
+                        objects,
+                        &pool,
+                        cond.dv_index_2,
+                        &[target as u16, cond.dv_index_1],
+                        rng,
+                    );
+                }
+
+                conditions[position] = cond;
+            }
+        }
+
+        objects.dv_cond.modified[dindex].conditions = conditions;
+    }
+
+    Ok(())
+}
+
 fn dv_cond_unlimited(
     preset: &Randomizer,
     objects: &mut Objects,
@@ -0,0 +1,124 @@
+use rand_xoshiro::rand_core::RngCore;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use crate::json::{DvRequirements, Parties};
+use crate::rand::Objects;
+
+// a requirement is a digivolution (dv_index_n, 0 for the rookie itself) at a
+// level (dv_level_n), a level of 0 means the slot is unused
+
+pub fn stage(objects: &Objects, index: u16) -> Option<usize> {
+    let digimon_id = objects
+        .digivolution_data
+        .original
+        .get((index as usize).checked_sub(9)?)?
+        .digimon_id;
+
+    [
+        &dmw3_consts::CHAMPIONS[..],
+        &dmw3_consts::ULTIMATES[..],
+        &dmw3_consts::MEGAS[..],
+        &dmw3_consts::MEGAPLUS[..],
+        &dmw3_consts::ULTRAS[..],
+    ]
+    .iter()
+    .position(|x| x.contains(&digimon_id))
+}
+
+fn random_level(preset: &Parties, rng: &mut Xoshiro256StarStar) -> u16 {
+    let min = preset.min_requirement_level.max(1);
+    let max = preset.max_requirement_level.max(min);
+
+    min + (rng.next_u64() % (max - min + 1) as u64) as u16
+}
+
+fn easy_level(preset: &Parties, level: u16) -> u16 {
+    ((level as u32 * preset.easy_requirement_percent as u32 / 100) as u16).max(1)
+}
+
+// picks another digivolution of the same stage, so prerequisites still come
+// before the digivolution that needs them
+pub fn random_prerequisite(
+    objects: &Objects,
+    pool: &[u16],
+    current: u16,
+    exclude: &[u16],
+    rng: &mut Xoshiro256StarStar,
+) -> u16 {
+    let current_stage = stage(objects, current);
+
+    let candidates: Vec<u16> = pool
+        .iter()
+        .copied()
+        .filter(|&x| stage(objects, x) == current_stage && !exclude.contains(&x))
+        .collect();
+
+    if candidates.is_empty() {
+        return current;
+    }
+
+    candidates[(rng.next_u64() % candidates.len() as u64) as usize]
+}
+
+pub fn patch(preset: &Parties, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
+    for r in 0..dmw3_consts::ROOKIE_COUNT {
+        let pool: Vec<u16> = objects.dv_cond.modified[r]
+            .conditions
+            .iter()
+            .map(|x| x.index as u16)
+            .collect();
+
+        for c in 0..pool.len() {
+            let cond = &objects.dv_cond.modified[r].conditions[c];
+
+            let mut dv_index_1 = cond.dv_index_1;
+            let mut dv_index_2 = cond.dv_index_2;
+            let mut dv_level_1 = cond.dv_level_1;
+            let mut dv_level_2 = cond.dv_level_2;
+
+            match preset.requirements {
+                DvRequirements::Keep => {}
+                DvRequirements::Random => {
+                    if dv_index_1 > 0 {
+                        dv_index_1 =
+                            random_prerequisite(objects, &pool, dv_index_1, &[pool[c]], rng);
+                    }
+
+                    if dv_index_2 > 0 {
+                        dv_index_2 = random_prerequisite(
+                            objects,
+                            &pool,
+                            dv_index_2,
+                            &[pool[c], dv_index_1],
+                            rng,
+                        );
+                    }
+
+                    if dv_level_1 > 0 {
+                        dv_level_1 = random_level(preset, rng);
+                    }
+
+                    if dv_level_2 > 0 {
+                        dv_level_2 = random_level(preset, rng);
+                    }
+                }
+                DvRequirements::Easy => {
+                    if dv_level_1 > 0 {
+                        dv_level_1 = easy_level(preset, dv_level_1);
+                    }
+
+                    if dv_level_2 > 0 {
+                        dv_level_2 = easy_level(preset, dv_level_2);
+                    }
+                }
+            }
+
+            let cond = &mut objects.dv_cond.modified[r].conditions[c];
+
+            cond.dv_index_1 = dv_index_1;
+            cond.dv_index_2 = dv_index_2;
+            cond.dv_level_1 = dv_level_1;
+            cond.dv_level_2 = dv_level_2;
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use rand_xoshiro::rand_core::RngCore;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use crate::json::Encounters;
+use crate::rand::{dmw3_structs::EnemyStats, Objects};
+
+pub const MAX_RESISTANCE: i16 = 999;
+
+const RESISTANCES: usize = 7;
+
+fn resistances(stats: &EnemyStats) -> [i16; RESISTANCES] {
+    [
+        stats.fir_res,
+        stats.wtr_res,
+        stats.ice_res,
+        stats.wnd_res,
+        stats.thd_res,
+        stats.mch_res,
+        stats.drk_res,
+    ]
+}
+
+fn set_resistances(stats: &mut EnemyStats, res: &[i32]) {
+    stats.fir_res = res[0] as i16;
+    stats.wtr_res = res[1] as i16;
+    stats.ice_res = res[2] as i16;
+    stats.wnd_res = res[3] as i16;
+    stats.thd_res = res[4] as i16;
+    stats.mch_res = res[5] as i16;
+    stats.drk_res = res[6] as i16;
+}
+
+fn random(floor: i32, ceil: i32, count: usize, rng: &mut Xoshiro256StarStar) -> Vec<i32> {
+    let range = (ceil - floor + 1) as u64;
+
+    (0..count)
+        .map(|_| floor + (rng.next_u64() % range) as i32)
+        .collect()
+}
+
+// random split of total into count values within [floor, ceil]
+fn distribute(
+    total: i32,
+    floor: i32,
+    ceil: i32,
+    count: usize,
+    rng: &mut Xoshiro256StarStar,
+) -> Vec<i32> {
+    let total = total.clamp(floor * count as i32, ceil * count as i32);
+
+    let weights: Vec<u64> = (0..count).map(|_| rng.next_u32() as u64 + 1).collect();
+    let sum: u64 = weights.iter().sum();
+    let spare = (total - floor * count as i32) as u64;
+
+    let mut values: Vec<i32> = weights
+        .iter()
+        .map(|x| floor + (x * spare / sum) as i32)
+        .collect();
+
+    // rounding and whatever went over ceil goes to values that still have room
+    let mut left = total - values.iter().sum::<i32>();
+
+    for value in &mut values {
+        let over = (*value - ceil).max(0);
+
+        *value -= over;
+        left += over;
+    }
+
+    while left > 0 {
+        let room: Vec<usize> = (0..count).filter(|&i| values[i] < ceil).collect();
+
+        if room.is_empty() {
+            break;
+        }
+
+        let i = room[(rng.next_u64() % room.len() as u64) as usize];
+        let added = (ceil - values[i]).min(left);
+
+        values[i] += added;
+        left -= added;
+    }
+
+    values
+}
+
+pub fn patch(preset: &Encounters, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
+    let min = preset.min_resistance as i32;
+    let max = preset.max_resistance as i32;
+
+    for stats in &mut objects.enemy_stats.modified {
+        let total: i32 = resistances(stats).iter().map(|x| *x as i32).sum();
+
+        let res = match preset.single_weakness {
+            true => {
+                // everything else stays in the upper half of the range
+                let weakness = (rng.next_u64() % RESISTANCES as u64) as usize;
+                let floor = ((min + max) / 2 + 1).min(max);
+
+                let mut rest = match preset.keep_total_resistance {
+                    true => distribute(total - min, floor, max, RESISTANCES - 1, rng),
+                    false => random(floor, max, RESISTANCES - 1, rng),
+                };
+
+                rest.insert(weakness, min);
+
+                rest
+            }
+            false => match preset.keep_total_resistance {
+                true => distribute(total, min, max, RESISTANCES, rng),
+                false => random(min, max, RESISTANCES, rng),
+            },
+        };
+
+        set_resistances(stats, &res);
+    }
+}
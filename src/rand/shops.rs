@@ -1,9 +1,17 @@
+use std::collections::HashSet;
+
 use crate::{rand::Objects, util::unique_vec};
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use rand_xoshiro::rand_core::RngCore;
 use rand_xoshiro::Xoshiro256StarStar;
 
-use crate::json::{ShopItems, Shops};
+use crate::json::{
+    GuaranteedPlacement, ItemCategory, ItemPool, ItemRef, PriceMode, ShopItems, Shops,
+};
+
+// first shop of every region known so far, 9 is the suzaku item shop.
+// the other regions' shops aren't mapped to indices yet
+pub const REGION_SHOPS: [u8; 2] = [0, 9];
 
 pub fn tnt_ironmon(objects: &mut Objects) {
     let f_ptr = objects.shops.modified[0].items;
@@ -40,35 +48,93 @@ pub fn healing_ironmon(objects: &mut Objects) {
     objects.item_shop_data.modified[0x47].sell_price = 2500;
 }
 
-pub fn item_in_ironmon(value: usize) -> bool {
-    // remove TNT Ball, Life Disk, Sober Disk, CC, Train Chips, Charisma Chips, Runner Shoes/Sandals and Binder Crest
-    // Charisma ring/gem
-    match value {
-        0x5a | 0x46 | 0x13e | 0x44 | 0x3e..0x42 | 0x14f..0x151 | 0x36 | 0x13f | 0x12e..0x130 => {
-            false
-        }
-        _ => true,
+pub fn predefined_pool(pool: &ShopItems) -> Option<ItemPool> {
+    match pool {
+        ShopItems::Buyable => Some(ItemPool {
+            name: String::from("buyable"),
+            categories: vec![ItemCategory::Buyable],
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }),
+        ShopItems::Sellable => Some(ItemPool {
+            name: String::from("sellable"),
+            categories: vec![ItemCategory::Sellable],
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }),
+        // remove TNT Ball, Life Disk, Sober Disk, CC, Train Chips, Charisma Chips, Runner Shoes/Sandals and Binder Crest
+        // Charisma ring/gem
+        ShopItems::Ironmon => Some(ItemPool {
+            name: String::from("ironmon"),
+            categories: vec![ItemCategory::Sellable],
+            include: Vec::new(),
+            exclude: [
+                0x5a, 0x46, 0x13e, 0x44, 0x3e, 0x3f, 0x40, 0x41, 0x14f, 0x150, 0x36, 0x13f, 0x12e,
+                0x12f,
+            ]
+            .into_iter()
+            .map(ItemRef::Id)
+            .collect(),
+        }),
+        ShopItems::Custom(_) => None,
     }
 }
 
+fn item_ids<T: FromIterator<u16>>(objects: &Objects, refs: &[ItemRef]) -> anyhow::Result<T> {
+    refs.iter()
+        .map(|item| match item {
+            ItemRef::Id(id) => Ok(*id),
+            ItemRef::Name(name) => objects
+                .items
+                .files
+                .iter()
+                .find_map(|(lang, file)| {
+                    file.file
+                        .files
+                        .iter()
+                        .position(|x| lang.decode(x).trim().eq_ignore_ascii_case(name.trim()))
+                })
+                .map(|x| x as u16)
+                .with_context(|| format!("unknown item {name}")),
+        })
+        .collect()
+}
+
 pub fn patch(
     preset: &Shops,
+    item_pools: &[ItemPool],
     objects: &mut Objects,
     rng: &mut Xoshiro256StarStar,
 ) -> anyhow::Result<()> {
-    let shoppable = shoppable(objects, &preset.items_only);
+    let shoppable = shoppable(objects, &preset.items_only, item_pools)?;
+
+    // items don't repeat within a shop
+    let shop_size = match preset.limit_shop_items_enabled {
+        true => preset.limit_shop_items as usize,
+        false => objects
+            .shop_items
+            .modified
+            .split(|x| *x == 0)
+            .map(|x| x.len())
+            .max()
+            .unwrap_or(0),
+    };
+
+    if shoppable.len() < shop_size {
+        return Err(anyhow!("shop item pool is too small"));
+    }
 
     match preset.limit_shop_items_enabled {
         true => {
-            randomize_limited(&preset.limit_shop_items, objects, rng, shoppable)?;
+            randomize_limited(preset, objects, rng, shoppable)?;
         }
         false => {
-            randomize_existing(objects, rng, shoppable);
+            randomize_existing(preset, objects, rng, shoppable, shop_size)?;
         }
     }
 
     if preset.sell_price {
-        randomize_sell_price(preset, objects, rng);
+        randomize_sell_price(preset, item_pools, objects, rng)?;
     }
 
     if preset.healing_ironmon {
@@ -77,70 +143,263 @@ pub fn patch(
 
     let len = objects.item_shop_data.modified.len();
     for i in 1..len {
+        let sell_price = objects.item_shop_data.modified[i].sell_price as u32;
+
         objects.item_shop_data.modified[i].buy_price =
-            2 * objects.item_shop_data.modified[i].sell_price;
+            (sell_price * preset.buy_sell_ratio as u32 / 100).min(u16::MAX as u32) as u16;
     }
 
     Ok(())
 }
 
-fn randomize_sell_price(preset: &Shops, objects: &mut Objects, rng: &mut Xoshiro256StarStar) {
-    let min_price = preset.min_sell_price;
-    let range = preset.max_sell_price - min_price + 1;
+struct PriceCurve {
+    mode: PriceMode,
+    min_sell_price: i64,
+    max_sell_price: i64,
+    variance: u16,
+}
+
+impl PriceCurve {
+    fn price(&self, vanilla: u16, rng: &mut Xoshiro256StarStar) -> u16 {
+        match self.mode {
+            PriceMode::Uniform => {
+                let range = self.max_sell_price - self.min_sell_price + 1;
+
+                self.min_sell_price as u16 + (rng.next_u64() % range as u64) as u16
+            }
+            PriceMode::Variance => {
+                let variance = self.variance as u64;
+                let factor = 100 - variance + rng.next_u64() % (variance * 2 + 1);
+
+                (vanilla as u64 * factor / 100).clamp(1, u16::MAX as u64) as u16
+            }
+        }
+    }
+}
+
+fn randomize_sell_price(
+    preset: &Shops,
+    item_pools: &[ItemPool],
+    objects: &mut Objects,
+    rng: &mut Xoshiro256StarStar,
+) -> anyhow::Result<()> {
+    let global = PriceCurve {
+        mode: preset.price_mode,
+        min_sell_price: preset.min_sell_price,
+        max_sell_price: preset.max_sell_price,
+        variance: preset.price_variance,
+    };
+
+    let mut rules = Vec::new();
+
+    for rule in &preset.price_rules {
+        let items: HashSet<u16> = shoppable(objects, &rule.pool, item_pools)?
+            .into_iter()
+            .collect();
+
+        let curve = PriceCurve {
+            mode: rule.price_mode,
+            min_sell_price: rule.min_sell_price,
+            max_sell_price: rule.max_sell_price,
+            variance: rule.price_variance,
+        };
+
+        rules.push((items, curve));
+    }
 
     let len = objects.item_shop_data.modified.len();
     for i in 1..len {
+        let vanilla = objects.item_shop_data.original[i].sell_price;
         let item = &mut objects.item_shop_data.modified[i];
 
         if item.sell_price != 0 && !(i as u16 == dmw3_consts::TNT_BALL_ID && preset.keep_tnt) {
-            item.sell_price = min_price as u16 + (rng.next_u64() % range as u64) as u16;
+            let curve = rules
+                .iter()
+                .find(|(items, _)| items.contains(&(i as u16)))
+                .map_or(&global, |(_, curve)| curve);
+
+            item.sell_price = curve.price(vanilla, rng);
         }
     }
+
+    Ok(())
 }
 
-pub fn shoppable(objects: &mut Objects, pool: &ShopItems) -> Vec<u16> {
+pub fn shoppable(
+    objects: &mut Objects,
+    pool: &ShopItems,
+    item_pools: &[ItemPool],
+) -> anyhow::Result<Vec<u16>> {
+    let pool = match pool {
+        ShopItems::Custom(name) => item_pools
+            .iter()
+            .find(|x| x.name == *name)
+            .cloned()
+            .with_context(|| format!("unknown item pool {name}"))?,
+        _ => predefined_pool(pool).context("missing predefined pool")?,
+    };
+
+    let include: HashSet<u16> = item_ids(objects, &pool.include)?;
+    let exclude: HashSet<u16> = item_ids(objects, &pool.exclude)?;
+
+    let stocked: HashSet<u16> = objects.shop_items.original.iter().copied().collect();
+    let dropped: HashSet<u16> = objects
+        .enemy_stats
+        .original
+        .iter()
+        .map(|x| x.droppable_item)
+        .collect();
+
     let len = objects.item_shop_data.original.len();
 
     let mut shoppable = Vec::new();
 
-    match pool {
-        ShopItems::Buyable => {
-            for i in 1..len {
-                if objects.item_shop_data.original[i].buy_price > 0 {
-                    shoppable.push(i as u16);
-                }
-            }
+    for i in 1..len {
+        let shop_data = &objects.item_shop_data.original[i];
+
+        let in_category = pool.categories.iter().any(|x| match x {
+            ItemCategory::Buyable => shop_data.buy_price > 0,
+            ItemCategory::Sellable => shop_data.sell_price > 0,
+            ItemCategory::Stocked => stocked.contains(&(i as u16)),
+            ItemCategory::Dropped => dropped.contains(&(i as u16)),
+        });
+
+        if (in_category || include.contains(&(i as u16))) && !exclude.contains(&(i as u16)) {
+            shoppable.push(i as u16);
         }
-        ShopItems::Sellable => {
-            for i in 1..len {
-                if objects.item_shop_data.original[i].sell_price > 0 {
-                    shoppable.push(i as u16);
+    }
+
+    Ok(unique_vec(shoppable))
+}
+
+// vanilla value of an item, sell only items count at the usual buy price
+fn item_value(objects: &Objects, item: u16) -> u32 {
+    let shop_data = &objects.item_shop_data.original[item as usize];
+
+    match shop_data.buy_price {
+        0 => shop_data.sell_price as u32 * 2,
+        buy_price => buy_price as u32,
+    }
+}
+
+// shops later in the game get a higher price ceiling, if too few items fit
+// under it the cheapest ones are used instead
+fn shop_pool(
+    preset: &Shops,
+    objects: &Objects,
+    shoppable: &[u16],
+    shop: usize,
+    shop_size: usize,
+) -> Vec<u16> {
+    if !preset.progression {
+        return shoppable.to_vec();
+    }
+
+    let shop_count = objects.shops.original.len().max(2) as u32;
+    let min = preset.progression_min_price as u32;
+    let max = (preset.progression_max_price as u32).max(min);
+
+    let shop = (shop as u32).min(shop_count - 1);
+
+    let ceiling = min + (max - min) * shop / (shop_count - 1);
+
+    let pool: Vec<u16> = shoppable
+        .iter()
+        .copied()
+        .filter(|x| item_value(objects, *x) <= ceiling)
+        .collect();
+
+    if pool.len() >= shop_size {
+        return pool;
+    }
+
+    let mut cheapest = shoppable.to_vec();
+    cheapest.sort_by_key(|x| item_value(objects, *x));
+    cheapest.truncate(shop_size);
+
+    shoppable
+        .iter()
+        .copied()
+        .filter(|x| cheapest.contains(x))
+        .collect()
+}
+
+// the guaranteed items of every shop, sizes is the item count of every shop
+fn guaranteed_items(
+    preset: &Shops,
+    objects: &Objects,
+    sizes: &[usize],
+    rng: &mut Xoshiro256StarStar,
+) -> anyhow::Result<Vec<Vec<u16>>> {
+    let shop_count = objects.shops.original.len();
+    let size = |shop: usize| sizes.get(shop).copied().unwrap_or(0);
+
+    let mut shops = vec![Vec::new(); shop_count];
+
+    if preset.guaranteed_items.is_empty() {
+        return Ok(shops);
+    }
+
+    let items: Vec<u16> = item_ids(objects, &preset.guaranteed_items)?;
+    let items = unique_vec(items);
+
+    if let Some(item) = items
+        .iter()
+        .find(|x| **x == 0 || **x as usize >= objects.item_shop_data.original.len())
+    {
+        return Err(anyhow!("invalid guaranteed item {item}"));
+    }
+
+    match preset.guaranteed_placement {
+        GuaranteedPlacement::Shops => {
+            for item in items {
+                for _ in 0..preset.guaranteed_shop_count {
+                    let free: Vec<usize> = (0..shop_count)
+                        .filter(|x| shops[*x].len() < size(*x) && !shops[*x].contains(&item))
+                        .collect();
+
+                    if free.is_empty() {
+                        return Err(anyhow!("not enough shop slots for guaranteed item {item}"));
+                    }
+
+                    shops[free[(rng.next_u64() % free.len() as u64) as usize]].push(item);
                 }
             }
         }
-        ShopItems::Ironmon => {
-            for i in 1..len {
-                if objects.item_shop_data.original[i].sell_price > 0 && item_in_ironmon(i) {
-                    shoppable.push(i as u16);
+        GuaranteedPlacement::Regions => {
+            if preset.region_shops.is_empty() {
+                return Err(anyhow!("no region shops for guaranteed items"));
+            }
+
+            for shop in &preset.region_shops {
+                let shop = *shop as usize;
+
+                if items.len() > size(shop) {
+                    return Err(anyhow!("more guaranteed items than items in shop {shop}"));
                 }
+
+                shops[shop].clone_from(&items);
             }
         }
     }
 
-    unique_vec(shoppable)
+    Ok(shops)
 }
 
 fn randomize_limited(
-    limit: &u8,
+    preset: &Shops,
     objects: &mut Objects,
     rng: &mut Xoshiro256StarStar,
     shoppable: Vec<u16>,
 ) -> anyhow::Result<()> {
+    let sizes = vec![preset.limit_shop_items as usize; objects.shops.original.len()];
+    let guaranteed = guaranteed_items(preset, objects, &sizes, rng)?;
+
     let mut ptr = objects.shops.modified.first().context("empty shops")?.items;
     for i in 0..objects.shops.original.len() {
-        let mut shoppable_arr = shoppable.clone();
+        let limit_deref = preset.limit_shop_items;
+        let mut shoppable_arr = shop_pool(preset, objects, &shoppable, i, limit_deref as usize);
         let shop = &mut objects.shops.modified[i];
-        let limit_deref = *limit;
 
         // limit + 1 (because of blank item)
         let lp1 = (limit_deref + 1) as usize;
@@ -148,7 +407,13 @@ fn randomize_limited(
         shop.items = ptr;
         shop.item_count = limit_deref as u32;
 
-        for j in 0..limit_deref as usize {
+        shoppable_arr.retain(|x| !guaranteed[i].contains(x));
+
+        for (j, item) in guaranteed[i].iter().enumerate() {
+            objects.shop_items.modified[i * lp1 + j] = *item;
+        }
+
+        for j in guaranteed[i].len()..limit_deref as usize {
             objects.shop_items.modified[i * lp1 + j] =
                 shoppable_arr.remove((rng.next_u64() % shoppable_arr.len() as u64) as usize);
         }
@@ -160,14 +425,50 @@ fn randomize_limited(
     Ok(())
 }
 
-fn randomize_existing(objects: &mut Objects, rng: &mut Xoshiro256StarStar, shoppable: Vec<u16>) {
-    let mut shoppable_arr = shoppable.clone();
-    for item in &mut objects.shop_items.modified {
-        if *item == 0 {
-            shoppable_arr = shoppable.clone();
+fn randomize_existing(
+    preset: &Shops,
+    objects: &mut Objects,
+    rng: &mut Xoshiro256StarStar,
+    shoppable: Vec<u16>,
+    shop_size: usize,
+) -> anyhow::Result<()> {
+    // shops keep their vanilla item count, separated by a blank item
+    let sizes: Vec<usize> = objects
+        .shop_items
+        .modified
+        .split(|x| *x == 0)
+        .map(|x| x.len())
+        .collect();
+    let guaranteed = guaranteed_items(preset, objects, &sizes, rng)?;
+
+    let pool = |objects: &Objects, shop: usize| {
+        let mut pool = shop_pool(preset, objects, &shoppable, shop, shop_size);
+
+        if let Some(items) = guaranteed.get(shop) {
+            pool.retain(|x| !items.contains(x));
+        }
+
+        pool
+    };
+
+    let mut shop = 0;
+    let mut slot = 0;
+    let mut shoppable_arr = pool(objects, shop);
+    for i in 0..objects.shop_items.modified.len() {
+        if objects.shop_items.modified[i] == 0 {
+            shop += 1;
+            slot = 0;
+            shoppable_arr = pool(objects, shop);
             continue;
         }
 
-        *item = shoppable_arr.remove((rng.next_u64() % shoppable_arr.len() as u64) as usize);
+        objects.shop_items.modified[i] = match guaranteed.get(shop).and_then(|x| x.get(slot)) {
+            Some(item) => *item,
+            None => shoppable_arr.remove((rng.next_u64() % shoppable_arr.len() as u64) as usize),
+        };
+
+        slot += 1;
     }
+
+    Ok(())
 }
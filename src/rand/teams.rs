@@ -0,0 +1,155 @@
+use rand_xoshiro::rand_core::RngCore;
+use rand_xoshiro::Xoshiro256StarStar;
+
+use crate::json::{Encounters, TeamTheme};
+use crate::rand::{
+    dmw3_structs::Pointer,
+    encounters::{is_boss, skip, stage},
+    Objects,
+};
+
+// PartyData.digimon length
+pub const MAX_TEAM_SIZE: u8 = 3;
+
+// team members (PartyData.digimon) point into the encounter table, empty
+// slots are null
+fn encounter_index(objects: &Objects, pointer: &Pointer) -> Option<usize> {
+    if !pointer.is_valid() || pointer.value < objects.overlay.value {
+        return None;
+    }
+
+    let offset = (pointer.to_index_overlay(objects.overlay.value) as usize)
+        .checked_sub(objects.encounters.index)?;
+
+    match offset % 0xc == 0 && offset / 0xc < objects.encounters.original.len() {
+        true => Some(offset / 0xc),
+        false => None,
+    }
+}
+
+fn encounter_pointer(objects: &Objects, index: usize) -> Pointer {
+    Pointer::from_index_overlay(
+        (objects.encounters.index + 0xc * index) as u32,
+        objects.overlay.value,
+    )
+}
+
+// strongest resistance
+fn element(objects: &Objects, digimon_id: u32) -> Option<usize> {
+    let stats = objects
+        .enemy_stats
+        .original
+        .iter()
+        .find(|x| x.digimon_id == digimon_id as u16)?;
+
+    let res = [
+        stats.fir_res,
+        stats.wtr_res,
+        stats.ice_res,
+        stats.wnd_res,
+        stats.thd_res,
+        stats.mch_res,
+        stats.drk_res,
+    ];
+
+    (0..res.len()).max_by_key(|&i| res[i])
+}
+
+fn theme(objects: &Objects, preset: &Encounters, rookies: &[u16], index: usize) -> Option<u32> {
+    let digimon_id = objects.encounters.modified[index].digimon_id;
+
+    match preset.team_theme {
+        TeamTheme::Random => Some(0),
+        TeamTheme::Element => element(objects, digimon_id).map(|x| x as u32),
+        TeamTheme::Stage => stage(digimon_id as u16, rookies),
+    }
+}
+
+fn fixed(objects: &Objects, preset: &Encounters, index: usize) -> bool {
+    let encounter = &objects.encounters.original[index];
+
+    skip(encounter, preset) || is_boss(encounter)
+}
+
+// rebuilds teams in place, so every StageEncounter.team_id keeps pointing at a team
+pub fn patch(
+    preset: &Encounters,
+    objects: &mut Objects,
+    rng: &mut Xoshiro256StarStar,
+) -> anyhow::Result<()> {
+    let rookies: Vec<u16> = objects
+        .rookie_data
+        .original
+        .iter()
+        .map(|x| x.digimon_id)
+        .collect();
+
+    let max_size = (preset.max_team_size as usize).clamp(1, MAX_TEAM_SIZE as usize);
+    let min_size = (preset.min_team_size as usize).clamp(1, max_size);
+
+    let candidates: Vec<usize> = (0..objects.encounters.original.len())
+        .filter(|&i| !fixed(objects, preset, i))
+        .collect();
+
+    for t in 0..objects.enemy_parties.original.len() {
+        let members: Vec<usize> = objects.enemy_parties.original[t]
+            .digimon
+            .iter()
+            .filter_map(|x| encounter_index(objects, x))
+            .collect();
+
+        // scripted and boss teams keep their members
+        if members.is_empty() || members.iter().any(|&i| fixed(objects, preset, i)) {
+            continue;
+        }
+
+        let lv = members
+            .iter()
+            .map(|&i| objects.encounters.original[i].lv as u32)
+            .sum::<u32>()
+            / members.len() as u32;
+
+        let mut nearby: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&i| {
+                (objects.encounters.original[i].lv as u32).abs_diff(lv)
+                    <= preset.level_band.max(1) as u32
+            })
+            .collect();
+
+        if nearby.is_empty() {
+            nearby = members;
+        }
+
+        let size = min_size + (rng.next_u64() % (max_size - min_size + 1) as u64) as usize;
+
+        let leader = nearby[(rng.next_u64() % nearby.len() as u64) as usize];
+        let leader_theme = theme(objects, preset, &rookies, leader);
+
+        let themed: Vec<usize> = nearby
+            .iter()
+            .copied()
+            .filter(|&i| theme(objects, preset, &rookies, i) == leader_theme)
+            .collect();
+
+        let mut team = vec![leader];
+
+        while team.len() < size {
+            team.push(themed[(rng.next_u64() % themed.len() as u64) as usize]);
+        }
+
+        let digimon: Vec<Pointer> = (0..MAX_TEAM_SIZE as usize)
+            .map(|k| match team.get(k) {
+                Some(&i) => encounter_pointer(objects, i),
+                None => Pointer { value: 0 },
+            })
+            .collect();
+
+        objects.enemy_parties.modified[t]
+            .digimon
+            .copy_from_slice(&digimon);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,703 @@
+use std::fmt::Write as _;
+
+use anyhow::Context;
+use async_std::fs::File;
+use async_std::io::WriteExt;
+use dmw3_structs::ScriptConditionStep;
+use serde::Serialize;
+
+use crate::{
+    hash::SeedHash,
+    lang::Language,
+    objects::{Objects, TextFileGroup},
+    rand::{auctions::full_prices, maps::type_script_add_item},
+};
+
+#[derive(Serialize)]
+pub struct Named {
+    pub id: u16,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct EncounterSpoiler {
+    pub index: usize,
+    pub original_digimon: Named,
+    pub digimon: Named,
+    pub lv: u16,
+    pub max_hp: u16,
+    pub multiplier: u16,
+}
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub str: u16,
+    pub def: u16,
+    pub spt: u16,
+    pub wis: u16,
+    pub spd: u16,
+}
+
+#[derive(Serialize)]
+pub struct Resistances {
+    pub fir: u16,
+    pub wtr: u16,
+    pub ice: u16,
+    pub wnd: u16,
+    pub thd: u16,
+    pub mch: u16,
+    pub drk: u16,
+}
+
+#[derive(Serialize)]
+pub struct RequirementSpoiler {
+    pub index: u16,
+    // none for a level of the rookie itself
+    pub digimon: Option<Named>,
+    pub level: u16,
+}
+
+#[derive(Serialize)]
+pub struct DigivolutionSpoiler {
+    pub index: u32,
+    pub digimon: Named,
+    pub requires: Vec<Named>,
+    pub requirements: Vec<RequirementSpoiler>,
+    pub signature: Named,
+    pub techs: Vec<Named>,
+}
+
+#[derive(Serialize)]
+pub struct RookieSpoiler {
+    pub digimon: Named,
+    pub starting_hp: u8,
+    pub starting_mp: u8,
+    pub hp_modifier: u8,
+    pub mp_modifier: u8,
+    pub exp_modifier: u8,
+    pub stats: Stats,
+    pub stat_affinities: Vec<u8>,
+    pub resistances: Resistances,
+    pub res_affinities: Vec<u8>,
+    pub signature: Named,
+    pub blasts: Vec<u8>,
+    pub digivolutions: Vec<DigivolutionSpoiler>,
+}
+
+#[derive(Serialize)]
+pub struct ShopItemSpoiler {
+    pub item: Named,
+    pub buy_price: u16,
+    pub sell_price: u16,
+}
+
+#[derive(Serialize)]
+pub struct ShopSpoiler {
+    pub index: usize,
+    pub items: Vec<ShopItemSpoiler>,
+}
+
+#[derive(Serialize)]
+pub struct AuctionSpoiler {
+    pub index: usize,
+    pub item: Named,
+    pub price: u32,
+}
+
+#[derive(Serialize)]
+pub struct CardSpoiler {
+    pub card_id: u16,
+    pub price: Option<i16>,
+}
+
+#[derive(Serialize)]
+pub struct CardShopSpoiler {
+    pub index: usize,
+    pub cards: Vec<CardSpoiler>,
+}
+
+#[derive(Serialize)]
+pub struct DropSpoiler {
+    pub digimon: Named,
+    pub item: Option<Named>,
+    pub drop_rate: u16,
+}
+
+#[derive(Serialize)]
+pub struct ItemBoxSpoiler {
+    pub file_name: String,
+    pub items: Vec<Named>,
+}
+
+#[derive(Serialize)]
+pub struct PackSpoiler {
+    pub index: usize,
+    pub digimon: Vec<Named>,
+    pub previews: Vec<Named>,
+}
+
+#[derive(Serialize)]
+pub struct Spoiler {
+    pub language: String,
+    pub seed_hash: SeedHash,
+    pub encounters: Vec<EncounterSpoiler>,
+    pub rookies: Vec<RookieSpoiler>,
+    pub packs: Vec<PackSpoiler>,
+    // set when every pack starts with the same rookie
+    pub starting_rookie: Option<Named>,
+    pub shops: Vec<ShopSpoiler>,
+    pub auctions: Vec<AuctionSpoiler>,
+    pub card_shops: Vec<CardShopSpoiler>,
+    pub item_boxes: Vec<ItemBoxSpoiler>,
+    pub drops: Vec<DropSpoiler>,
+}
+
+fn named(group: &TextFileGroup, lang: &Language, id: u16, kind: &str) -> Named {
+    let name = match group.files.get(lang) {
+        Some(file) => match file.file.files.get(id as usize) {
+            Some(name) => lang.decode(name),
+            None => format!("Unknown {kind} {id}"),
+        },
+        None => format!("{kind} {id}"),
+    };
+
+    Named { id, name }
+}
+
+fn named_item(objects: &Objects, lang: &Language, id: u16) -> anyhow::Result<Named> {
+    objects
+        .items
+        .files
+        .get(lang)
+        .context("failed to get by lang")?;
+
+    Ok(named(&objects.items, lang, id, "item"))
+}
+
+fn named_digimon(objects: &Objects, lang: &Language, id: u16) -> Named {
+    named(&objects.digimon_names, lang, id, "digimon")
+}
+
+fn named_tech(objects: &Objects, lang: &Language, id: u16) -> Named {
+    named(&objects.move_names, lang, id, "tech")
+}
+
+// indices below 9 are rookies, the rest are digivolutions
+fn named_digivolution(objects: &Objects, lang: &Language, index: u16) -> anyhow::Result<Named> {
+    let digimon_id = match (index as usize).checked_sub(9) {
+        Some(dv) => {
+            objects
+                .digivolution_data
+                .modified
+                .get(dv)
+                .context("failed to find digivolution")?
+                .digimon_id
+        }
+        None => {
+            objects
+                .rookie_data
+                .modified
+                .get(index as usize)
+                .context("failed to find rookie")?
+                .digimon_id
+        }
+    };
+
+    Ok(named_digimon(objects, lang, digimon_id))
+}
+
+fn encounters(objects: &Objects, lang: &Language) -> Vec<EncounterSpoiler> {
+    objects
+        .encounters
+        .modified
+        .iter()
+        .zip(objects.encounters.original.iter())
+        .enumerate()
+        .map(|(index, (modified, original))| EncounterSpoiler {
+            index,
+            original_digimon: named_digimon(objects, lang, original.digimon_id as u16),
+            digimon: named_digimon(objects, lang, modified.digimon_id as u16),
+            lv: modified.lv,
+            max_hp: modified.max_hp,
+            multiplier: modified.multiplier,
+        })
+        .collect()
+}
+
+fn rookies(objects: &Objects, lang: &Language) -> anyhow::Result<Vec<RookieSpoiler>> {
+    let mut result = Vec::new();
+
+    for (r, rookie) in objects.rookie_data.modified.iter().enumerate() {
+        let mut digivolutions = Vec::new();
+
+        for cond in &objects.dv_cond.modified[r].conditions {
+            let digivolution = objects
+                .digivolution_data
+                .modified
+                .get(cond.index as usize - 9)
+                .context("failed to find digivolution")?;
+
+            let requires = [cond.dv_index_1, cond.dv_index_2]
+                .into_iter()
+                .filter(|x| *x > 0)
+                .map(|x| named_digivolution(objects, lang, x))
+                .collect::<anyhow::Result<_>>()?;
+
+            // index 0 is a level of the rookie itself
+            let requirements = [
+                (cond.dv_index_1, cond.dv_level_1),
+                (cond.dv_index_2, cond.dv_level_2),
+            ]
+            .into_iter()
+            .filter(|(_, level)| *level > 0)
+            .map(|(index, level)| {
+                let digimon = match index {
+                    0 => None,
+                    index => Some(named_digivolution(objects, lang, index)?),
+                };
+
+                Ok(RequirementSpoiler {
+                    index,
+                    digimon,
+                    level,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+            digivolutions.push(DigivolutionSpoiler {
+                index: cond.index,
+                digimon: named_digimon(objects, lang, digivolution.digimon_id),
+                requires,
+                requirements,
+                signature: named_tech(objects, lang, digivolution.ori_tech as u16),
+                techs: digivolution
+                    .tech
+                    .iter()
+                    .filter(|x| **x != 0)
+                    .map(|x| named_tech(objects, lang, *x as u16))
+                    .collect(),
+            });
+        }
+
+        result.push(RookieSpoiler {
+            digimon: named_digimon(objects, lang, rookie.digimon_id),
+            starting_hp: rookie.starting_hp,
+            starting_mp: rookie.starting_mp,
+            hp_modifier: rookie.hp_modifier,
+            mp_modifier: rookie.mp_modifier,
+            exp_modifier: rookie.exp_modifier,
+            stats: Stats {
+                str: rookie.str,
+                def: rookie.def,
+                spt: rookie.spt,
+                wis: rookie.wis,
+                spd: rookie.spd,
+            },
+            stat_affinities: rookie.stat_offsets.to_vec(),
+            resistances: Resistances {
+                fir: rookie.fir_res,
+                wtr: rookie.wtr_res,
+                ice: rookie.ice_res,
+                wnd: rookie.wnd_res,
+                thd: rookie.thd_res,
+                mch: rookie.mch_res,
+                drk: rookie.drk_res,
+            },
+            res_affinities: rookie.res_offsets.to_vec(),
+            signature: named_tech(objects, lang, rookie.ori_tech as u16),
+            blasts: rookie
+                .blast_indices
+                .iter()
+                .filter(|x| **x != 0)
+                .copied()
+                .collect(),
+            digivolutions,
+        });
+    }
+
+    Ok(result)
+}
+
+fn packs(objects: &Objects, lang: &Language) -> anyhow::Result<Vec<PackSpoiler>> {
+    let digimon = |rookie: u32| -> anyhow::Result<Named> {
+        let digimon_id = objects
+            .rookie_data
+            .modified
+            .get(rookie as usize)
+            .context("failed to find rookie")?
+            .digimon_id;
+
+        Ok(named_digimon(objects, lang, digimon_id))
+    };
+
+    let mut result = Vec::new();
+
+    for (index, (pack, previews)) in objects
+        .parties
+        .modified
+        .chunks(3)
+        .zip(objects.pack_previews.modified.chunks(3))
+        .enumerate()
+    {
+        result.push(PackSpoiler {
+            index,
+            digimon: pack
+                .iter()
+                .map(|x| digimon(*x as u32))
+                .collect::<anyhow::Result<_>>()?,
+            previews: previews
+                .iter()
+                .map(|x| digimon(*x))
+                .collect::<anyhow::Result<_>>()?,
+        });
+    }
+
+    Ok(result)
+}
+
+fn starting_rookie(packs: &[PackSpoiler]) -> Option<Named> {
+    let leader = packs.first()?.digimon.first()?;
+
+    packs
+        .iter()
+        .all(|x| x.digimon.first().map(|x| x.id) == Some(leader.id))
+        .then(|| Named {
+            id: leader.id,
+            name: leader.name.clone(),
+        })
+}
+
+fn shops(objects: &Objects, lang: &Language) -> anyhow::Result<Vec<ShopSpoiler>> {
+    let base = objects.shops.modified.first().context("empty shops")?.items;
+    let mut result = Vec::new();
+
+    for (index, shop) in objects.shops.modified.iter().enumerate() {
+        let start = (shop.items.value - base.value) as usize / 2;
+        let mut items = Vec::new();
+
+        for item in &objects.shop_items.modified[start..start + shop.item_count as usize] {
+            let shop_data = &objects.item_shop_data.modified[*item as usize];
+
+            items.push(ShopItemSpoiler {
+                item: named_item(objects, lang, *item)?,
+                buy_price: shop_data.buy_price,
+                sell_price: shop_data.sell_price,
+            });
+        }
+
+        result.push(ShopSpoiler { index, items });
+    }
+
+    Ok(result)
+}
+
+fn auctions(objects: &Objects, lang: &Language) -> anyhow::Result<Vec<AuctionSpoiler>> {
+    let prices = full_prices(objects);
+    let mut result = Vec::new();
+
+    for (index, auction_set) in objects.auction_items.modified.iter().enumerate() {
+        let item = match auction_set.item {
+            ScriptConditionStep::Step { value, .. } => value,
+            ScriptConditionStep::EndStep => unreachable!("EndStep shouldn't be in mapped"),
+        };
+
+        result.push(AuctionSpoiler {
+            index,
+            item: named_item(objects, lang, item)?,
+            price: prices[index][4],
+        });
+    }
+
+    Ok(result)
+}
+
+// card names aren't in any of the text files read so far, so cards are
+// listed by id
+fn card_shops(objects: &Objects) -> Vec<CardShopSpoiler> {
+    (0..objects.card_shops.modified.len())
+        .map(|index| CardShopSpoiler {
+            index,
+            cards: objects.card_shop_items.modified[index * 8..index * 8 + 6]
+                .iter()
+                .map(|card_id| CardSpoiler {
+                    card_id: *card_id,
+                    price: objects
+                        .card_pricing
+                        .modified
+                        .iter()
+                        .find(|x| x.card_id as u16 == *card_id)
+                        .map(|x| x.pricing),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn item_boxes(objects: &Objects, lang: &Language) -> anyhow::Result<Vec<ItemBoxSpoiler>> {
+    let mut result = Vec::new();
+
+    for map in &objects.map_objects {
+        let Some(entities) = &map.entities else {
+            continue;
+        };
+
+        let mut items = Vec::new();
+
+        for mapped_entity in &entities.mapped {
+            if !dmw3_consts::ITEM_BOX_SPRITES.contains(&mapped_entity.data.sprite) {
+                continue;
+            }
+
+            for logic in &mapped_entity.logics {
+                for script in &logic.scripts {
+                    if let ScriptConditionStep::Step {
+                        value,
+                        condition_type,
+                        ..
+                    } = script
+                    {
+                        if type_script_add_item(*condition_type) {
+                            items.push(named_item(objects, lang, *value)?);
+                        }
+                    }
+                }
+            }
+        }
+
+        if !items.is_empty() {
+            result.push(ItemBoxSpoiler {
+                file_name: map.file_name.clone(),
+                items,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn drops(objects: &Objects, lang: &Language) -> anyhow::Result<Vec<DropSpoiler>> {
+    let mut result = Vec::new();
+
+    for stats in &objects.enemy_stats.modified {
+        let item = match stats.droppable_item {
+            0 => None,
+            id => Some(named_item(objects, lang, id)?),
+        };
+
+        result.push(DropSpoiler {
+            digimon: named_digimon(objects, lang, stats.digimon_id),
+            item,
+            drop_rate: stats.drop_rate,
+        });
+    }
+
+    Ok(result)
+}
+
+pub fn spoiler(
+    objects: &Objects,
+    seed_hash: &SeedHash,
+    lang: &Language,
+) -> anyhow::Result<Spoiler> {
+    let packs = packs(objects, lang)?;
+
+    Ok(Spoiler {
+        language: String::from(lang.to_folder()),
+        seed_hash: seed_hash.clone(),
+        encounters: encounters(objects, lang),
+        rookies: rookies(objects, lang)?,
+        starting_rookie: starting_rookie(&packs),
+        packs,
+        shops: shops(objects, lang)?,
+        auctions: auctions(objects, lang)?,
+        card_shops: card_shops(objects),
+        item_boxes: item_boxes(objects, lang)?,
+        drops: drops(objects, lang)?,
+    })
+}
+
+fn names(named: &[Named]) -> String {
+    let names: Vec<&str> = named.iter().map(|x| x.name.as_str()).collect();
+
+    names.join(", ")
+}
+
+pub fn to_markdown(spoiler: &Spoiler) -> anyhow::Result<String> {
+    let mut md = String::new();
+
+    writeln!(md, "# Spoiler ({})", spoiler.language)?;
+    writeln!(md, "\nSeed hash: {}", spoiler.seed_hash)?;
+
+    writeln!(md, "\n## Encounters\n")?;
+    writeln!(md, "| # | Original | Digimon | Lv | HP | Multiplier |")?;
+    writeln!(md, "|---|---|---|---|---|---|")?;
+    for encounter in &spoiler.encounters {
+        writeln!(
+            md,
+            "| {} | {} | {} | {} | {} | {} |",
+            encounter.index,
+            encounter.original_digimon.name,
+            encounter.digimon.name,
+            encounter.lv,
+            encounter.max_hp,
+            encounter.multiplier
+        )?;
+    }
+
+    writeln!(md, "\n## Starting packs\n")?;
+    if let Some(starting_rookie) = &spoiler.starting_rookie {
+        writeln!(md, "Every pack starts with {}\n", starting_rookie.name)?;
+    }
+    writeln!(md, "| Pack | Digimon | Preview |")?;
+    writeln!(md, "|---|---|---|")?;
+    for pack in &spoiler.packs {
+        writeln!(
+            md,
+            "| {} | {} | {} |",
+            pack.index,
+            names(&pack.digimon),
+            names(&pack.previews)
+        )?;
+    }
+
+    writeln!(md, "\n## Rookies")?;
+    for rookie in &spoiler.rookies {
+        writeln!(md, "\n### {}\n", rookie.digimon.name)?;
+        writeln!(
+            md,
+            "- HP {} (affinity {}), MP {} (affinity {}), EXP affinity {}",
+            rookie.starting_hp,
+            rookie.hp_modifier,
+            rookie.starting_mp,
+            rookie.mp_modifier,
+            rookie.exp_modifier
+        )?;
+        writeln!(
+            md,
+            "- STR {} / DEF {} / SPT {} / WIS {} / SPD {} (affinities {:?})",
+            rookie.stats.str,
+            rookie.stats.def,
+            rookie.stats.spt,
+            rookie.stats.wis,
+            rookie.stats.spd,
+            rookie.stat_affinities
+        )?;
+        writeln!(
+            md,
+            "- FIR {} / WTR {} / ICE {} / WND {} / THD {} / MCH {} / DRK {} (affinities {:?})",
+            rookie.resistances.fir,
+            rookie.resistances.wtr,
+            rookie.resistances.ice,
+            rookie.resistances.wnd,
+            rookie.resistances.thd,
+            rookie.resistances.mch,
+            rookie.resistances.drk,
+            rookie.res_affinities
+        )?;
+        writeln!(md, "- Signature tech {}", rookie.signature.name)?;
+        writeln!(md, "- Blasts {:?}\n", rookie.blasts)?;
+
+        writeln!(md, "| Index | Digimon | Requires | Signature | Techs |")?;
+        writeln!(md, "|---|---|---|---|---|")?;
+        for digivolution in &rookie.digivolutions {
+            let requirements: Vec<String> = digivolution
+                .requirements
+                .iter()
+                .map(|x| match &x.digimon {
+                    None => format!("lv {}", x.level),
+                    Some(digimon) => format!("{} lv {}", digimon.name, x.level),
+                })
+                .collect();
+
+            writeln!(
+                md,
+                "| {} | {} | {} | {} | {} |",
+                digivolution.index,
+                digivolution.digimon.name,
+                requirements.join(", "),
+                digivolution.signature.name,
+                names(&digivolution.techs)
+            )?;
+        }
+    }
+
+    writeln!(md, "\n## Shops")?;
+    for shop in &spoiler.shops {
+        writeln!(md, "\n### Shop {}\n", shop.index)?;
+        for item in &shop.items {
+            writeln!(
+                md,
+                "- {} ({} / sells for {})",
+                item.item.name, item.buy_price, item.sell_price
+            )?;
+        }
+    }
+
+    writeln!(md, "\n## Auctions\n")?;
+    for auction in &spoiler.auctions {
+        writeln!(
+            md,
+            "- {}: {} for {} BIT",
+            auction.index, auction.item.name, auction.price
+        )?;
+    }
+
+    writeln!(md, "\n## Card shops")?;
+    for card_shop in &spoiler.card_shops {
+        writeln!(md, "\n### Card shop {}\n", card_shop.index)?;
+        for card in &card_shop.cards {
+            match card.price {
+                Some(price) => writeln!(md, "- Card {} ({})", card.card_id, price)?,
+                None => writeln!(md, "- Card {}", card.card_id)?,
+            }
+        }
+    }
+
+    writeln!(md, "\n## Item boxes")?;
+    for item_box in &spoiler.item_boxes {
+        writeln!(md, "\n### {}\n", item_box.file_name)?;
+        for item in &item_box.items {
+            writeln!(md, "- {}", item.name)?;
+        }
+    }
+
+    writeln!(md, "\n## Drops\n")?;
+    writeln!(md, "| Digimon | Item | Rate |")?;
+    writeln!(md, "|---|---|---|")?;
+    for drop in &spoiler.drops {
+        match &drop.item {
+            Some(item) => writeln!(
+                md,
+                "| {} | {} | {} |",
+                drop.digimon.name, item.name, drop.drop_rate
+            )?,
+            None => writeln!(md, "| {} | - | - |", drop.digimon.name)?,
+        }
+    }
+
+    Ok(md)
+}
+
+pub async fn write_spoilers(
+    objects: &Objects,
+    seed_hash: &SeedHash,
+    output_dir: &str,
+) -> anyhow::Result<()> {
+    for lang in objects.executable.languages() {
+        let spoiler = spoiler(objects, seed_hash, lang)?;
+
+        let mut json_file =
+            File::create(format!("{}/spoiler_{}.json", output_dir, lang.to_folder())).await?;
+
+        json_file
+            .write_all(serde_json::to_string_pretty(&spoiler)?.as_bytes())
+            .await?;
+
+        let mut md_file =
+            File::create(format!("{}/spoiler_{}.md", output_dir, lang.to_folder())).await?;
+
+        md_file.write_all(to_markdown(&spoiler)?.as_bytes()).await?;
+    }
+
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use std::collections::HashSet;
+use std::path::PathBuf;
 
+use async_std::fs;
+use async_std::stream::StreamExt;
 use rand_xoshiro::rand_core::RngCore;
 use rand_xoshiro::Xoshiro256StarStar;
 use std::hash::Hash;
@@ -60,3 +63,27 @@ where
 
     rv
 }
+
+pub async fn copy_dir(from: &PathBuf, to: &PathBuf) -> anyhow::Result<()> {
+    let mut stack: Vec<(async_std::path::PathBuf, async_std::path::PathBuf)> =
+        vec![(from.clone().into(), to.clone().into())];
+
+    while let Some((from, to)) = stack.pop() {
+        fs::create_dir_all(&to).await?;
+
+        let mut entries = fs::read_dir(&from).await?;
+
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            let target = to.join(entry.file_name());
+
+            if entry.file_type().await?.is_dir() {
+                stack.push((entry.path(), target));
+            } else {
+                fs::copy(entry.path(), target).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
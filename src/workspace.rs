@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use async_std::fs;
+use async_std::stream::StreamExt;
+
+pub struct Workspace {
+    pub rom: PathBuf,
+    pub rom_name: String,
+    pub root: PathBuf,
+    extract_dir: String,
+    build_dir: String,
+    temporary: bool,
+}
+
+fn to_string(path: &PathBuf) -> anyhow::Result<String> {
+    Ok(String::from(
+        path.to_str().context("Failed to_str conversion")?,
+    ))
+}
+
+impl Workspace {
+    // every run gets its own directory so concurrent runs never share files
+    pub async fn new(rom: &PathBuf) -> anyhow::Result<Workspace> {
+        let rom_name = rom
+            .file_name()
+            .context("Failed file name get")?
+            .to_str()
+            .context("Failed to_str conversion")?;
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+
+        let root = std::env::temp_dir().join("dmw3_randomizer").join(format!(
+            "{}-{}-{}",
+            rom_name,
+            std::process::id(),
+            nanos
+        ));
+
+        let workspace = Workspace {
+            rom: rom.clone(),
+            rom_name: String::from(rom_name),
+            extract_dir: to_string(&root.join("extract"))?,
+            build_dir: to_string(&root.join("build"))?,
+            root,
+            temporary: true,
+        };
+
+        fs::create_dir_all(&workspace.extract_dir).await?;
+        fs::create_dir_all(&workspace.build_dir).await?;
+
+        Ok(workspace)
+    }
+
+    // fixed extract/<rom> layout used by the mod tools, kept between invocations
+    pub async fn persistent(rom: &PathBuf, build_name: &str) -> anyhow::Result<Workspace> {
+        let rom_name = rom
+            .file_name()
+            .context("Failed file name get")?
+            .to_str()
+            .context("Failed to_str conversion")?;
+
+        let workspace = Workspace {
+            rom: rom.clone(),
+            rom_name: String::from(rom_name),
+            root: PathBuf::from(format!("extract/{}", rom_name)),
+            extract_dir: format!("extract/{}", rom_name),
+            build_dir: format!("randomized/{}/{}", rom_name, build_name),
+            temporary: false,
+        };
+
+        fs::create_dir_all(&workspace.extract_dir).await?;
+        fs::create_dir_all(&workspace.build_dir).await?;
+
+        Ok(workspace)
+    }
+
+    pub fn extract_dir(&self) -> &str {
+        &self.extract_dir
+    }
+
+    pub fn build_dir(&self) -> &str {
+        &self.build_dir
+    }
+
+    // copies the built image and spoilers to <output_root>/<rom>/<file_name>
+    pub async fn publish(&self, output_root: &PathBuf, file_name: &str) -> anyhow::Result<PathBuf> {
+        let destination: async_std::path::PathBuf =
+            output_root.join(&self.rom_name).join(file_name).into();
+
+        fs::create_dir_all(&destination).await?;
+
+        let mut entries = fs::read_dir(&self.build_dir).await?;
+
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+
+            if entry.file_type().await?.is_file() {
+                fs::copy(entry.path(), destination.join(entry.file_name())).await?;
+            }
+        }
+
+        Ok(destination.into())
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+}